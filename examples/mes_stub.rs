@@ -0,0 +1,41 @@
+//! 本地 MES 桩, 打印收到的结果
+//!
+//! `cargo run --example mes_stub -- 8080` 正常接收;
+//! 追加 `down` 时返回 503, 用于验证离线队列
+use tokio::{
+  io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+  net::TcpListener,
+};
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  let port = args.first().map(String::as_str).unwrap_or("8080");
+  let down = args.iter().any(|x| x == "down");
+  let listener = TcpListener::bind(format!("127.0.0.1:{port}")).await?;
+  println!("MES stub: http://127.0.0.1:{port} {}", if down { "(503)" } else { "" });
+  loop {
+    let (stream, _) = listener.accept().await?;
+    tokio::spawn(async move {
+      let mut reader = BufReader::new(stream);
+      let mut request = String::new();
+      let mut length = 0;
+      loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.unwrap_or(0) == 0 || line == "\r\n" {
+          break;
+        }
+        if let Some(v) = line.to_lowercase().strip_prefix("content-length:") {
+          length = v.trim().parse().unwrap_or(0);
+        }
+        request.push_str(&line);
+      }
+      let mut body = vec![0; length];
+      let _ = reader.read_exact(&mut body).await;
+      println!("{}{}\n", request, String::from_utf8_lossy(&body));
+      let status = if down { "503 Service Unavailable" } else { "200 OK" };
+      let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+      let _ = reader.get_mut().write_all(response.as_bytes()).await;
+    });
+  }
+}
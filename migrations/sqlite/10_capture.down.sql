@@ -0,0 +1,2 @@
+ALTER TABLE externApp DROP COLUMN capture;
+ALTER TABLE test_run DROP COLUMN vars;
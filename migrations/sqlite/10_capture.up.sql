@@ -0,0 +1,2 @@
+ALTER TABLE externApp ADD COLUMN capture TEXT;
+ALTER TABLE test_run ADD COLUMN vars JSON NOT NULL DEFAULT '{}';
@@ -0,0 +1 @@
+ALTER TABLE externApp DROP COLUMN condition;
@@ -0,0 +1 @@
+ALTER TABLE externApp ADD COLUMN condition JSON NOT NULL DEFAULT '{}';
@@ -0,0 +1 @@
+ALTER TABLE externApp ADD COLUMN params JSON NOT NULL DEFAULT '{}';
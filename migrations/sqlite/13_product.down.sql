@@ -0,0 +1,2 @@
+ALTER TABLE runner DROP COLUMN product_id;
+DROP TABLE IF EXISTS product;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS product (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    sn_pattern TEXT NOT NULL DEFAULT '',
+    created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO product(id, name, sn_pattern) VALUES(0, 'CB72I18SI', '^CB72I18SI-[0-9A-Z]{19}$');
+
+ALTER TABLE runner ADD COLUMN product_id INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE runner DROP COLUMN station_id;
+DROP TABLE IF EXISTS station;
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS station (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    steps JSON NOT NULL DEFAULT '[]',
+    sn_pattern TEXT NOT NULL DEFAULT '',
+    upload_url TEXT NOT NULL DEFAULT '',
+    pre_hook TEXT NOT NULL DEFAULT '',
+    post_hook TEXT NOT NULL DEFAULT '',
+    created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO station(id, name) VALUES(0, 'IFT'), (1, 'FCT'), (2, 'RUNIN');
+
+ALTER TABLE runner ADD COLUMN station_id INTEGER NOT NULL DEFAULT 0;
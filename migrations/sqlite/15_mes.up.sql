@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS mes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    headers JSON NOT NULL DEFAULT '{}',
+    token TEXT NOT NULL DEFAULT '',
+    timeout INTEGER NOT NULL DEFAULT 10,
+    retry_interval INTEGER NOT NULL DEFAULT 30,
+    upload_steps BOOLEAN NOT NULL DEFAULT 1,
+    created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO mes(id) VALUES(0);
+
+CREATE TABLE IF NOT EXISTS outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL,
+    payload JSON NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS report (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    folder TEXT NOT NULL DEFAULT '#origin#/reports',
+    fname TEXT NOT NULL DEFAULT '#sn#_#station#_#timestamp#',
+    html BOOLEAN NOT NULL DEFAULT 1,
+    junit BOOLEAN NOT NULL DEFAULT 1,
+    csv BOOLEAN NOT NULL DEFAULT 1,
+    created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO report(id) VALUES(0);
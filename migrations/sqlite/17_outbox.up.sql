@@ -0,0 +1,2 @@
+ALTER TABLE outbox ADD COLUMN next_at timestamptz;
+ALTER TABLE outbox ADD COLUMN dead BOOLEAN NOT NULL DEFAULT 0;
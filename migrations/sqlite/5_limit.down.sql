@@ -0,0 +1 @@
+ALTER TABLE externApp DROP COLUMN limits;
\ No newline at end of file
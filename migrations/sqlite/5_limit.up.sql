@@ -0,0 +1 @@
+ALTER TABLE externApp ADD COLUMN limits JSON NOT NULL DEFAULT '{}';
@@ -0,0 +1 @@
+ALTER TABLE externApp DROP COLUMN depends_on;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE externApp ADD COLUMN depends_on JSON NOT NULL DEFAULT '[]';
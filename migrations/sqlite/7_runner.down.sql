@@ -0,0 +1 @@
+DROP TABLE runner;
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS runner (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    max_parallel INTEGER NOT NULL DEFAULT 4,
+    created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO runner(id) VALUES(0);
@@ -0,0 +1 @@
+ALTER TABLE externApp DROP COLUMN retry;
\ No newline at end of file
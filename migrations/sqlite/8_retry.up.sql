@@ -0,0 +1 @@
+ALTER TABLE externApp ADD COLUMN retry JSON NOT NULL DEFAULT '{}';
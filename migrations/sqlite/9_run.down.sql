@@ -0,0 +1,2 @@
+DROP TABLE test_step_result;
+DROP TABLE test_run;
\ No newline at end of file
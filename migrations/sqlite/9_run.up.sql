@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS test_run (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    sn TEXT NOT NULL,
+    station TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    result TEXT NOT NULL,
+    started_at timestamptz NOT NULL,
+    ended_at timestamptz
+);
+CREATE INDEX IF NOT EXISTS idx_test_run_sn ON test_run(sn);
+CREATE INDEX IF NOT EXISTS idx_test_run_started_at ON test_run(started_at);
+CREATE TABLE IF NOT EXISTS test_step_result (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL REFERENCES test_run(id) ON DELETE CASCADE,
+    app_id INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    label TEXT NOT NULL,
+    attempt INTEGER NOT NULL,
+    state TEXT NOT NULL,
+    stdout TEXT NOT NULL DEFAULT '',
+    stderr TEXT NOT NULL DEFAULT '',
+    res_value JSON NOT NULL DEFAULT 'null',
+    limits JSON NOT NULL DEFAULT '{}',
+    violation TEXT,
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    created_at timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX IF NOT EXISTS idx_test_step_result_run_id ON test_step_result(run_id);
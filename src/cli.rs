@@ -0,0 +1,122 @@
+//! 命令行模式, 用于批量部署测试序列
+//!
+//! - `export <文件>`: 导出测试序列
+//! - `import <文件> [--replace] [--dry-run]`: 显示差异后导入, 默认合并; `--dry-run`只显示差异
+//! - `dry-run [SN]`: 检查当前工站的步骤, 按执行顺序列出替换变量后的命令, 不执行
+//!
+//! 文件格式由扩展名决定(`.toml`/`.json`/`.csv`), 相对路径基于当前目录
+//!
+//! Windows 发布版为窗口程序, 命令行模式下附加到启动它的控制台输出结果; 没有控制台时(如双击快捷方式)只有退出码
+use std::collections::HashMap;
+
+use e_utils::parse::{MyParseFormat as _, ParseResultDefault as _};
+use sqlx::SqlitePool;
+
+use crate::{
+  data::DB_SQLITE,
+  db::apis::{
+    app::{save_all_extend_apps, select_all_extend_apps},
+    load_all_data,
+  },
+  plugins::{
+    exchange::{self, Mode},
+    schedule::{schedule, select},
+    template::Template,
+    validate::{dry_run, validate},
+  },
+  res::{Error, Result},
+};
+
+const USAGE: &str = "用法: export <文件> | import <文件> [--replace] [--dry-run] | dry-run [SN]";
+
+/// 命令行参数为`export`/`import`时执行并返回结果, 否则返回`None`启动界面
+pub fn run() -> Option<Result<()>> {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  match args.first().map(String::as_str) {
+    Some("export" | "import" | "dry-run") => {
+      attach_console();
+      Some(execute(args))
+    }
+    _ => None,
+  }
+}
+
+/// 附加到父进程的控制台, 使`println!`和错误信息可见; 已有控制台或没有父控制台时无效果
+#[cfg(windows)]
+fn attach_console() {
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn AttachConsole(process_id: u32) -> i32;
+  }
+  const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+  // SAFETY: 只传入常量参数, 失败时返回0, 忽略即可
+  unsafe {
+    AttachConsole(ATTACH_PARENT_PROCESS);
+  }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+#[tokio::main]
+async fn execute(args: Vec<String>) -> Result<()> {
+  let flag = |name: &str| args.iter().any(|x| x == name);
+  let value = args.iter().skip(1).find(|x| !x.starts_with("--"));
+  let pool = {
+    let mut db = DB_SQLITE.write().await;
+    db.a_connect().await?;
+    db.pool()
+  };
+  if args[0] == "dry-run" {
+    return preview(&pool, value.cloned().unwrap_or_default()).await;
+  }
+  let path = value.ok_or(Error::Unsupport(USAGE.into()))?;
+  let mut apps = select_all_extend_apps(&pool).await?;
+  apps.sort_by_key(|x| x.priority);
+  if args[0] == "export" {
+    exchange::export_file(apps.clone(), path).await?;
+    println!("已导出{}个步骤: {path}", apps.len());
+    return Ok(());
+  }
+  let mode = match flag("--replace") {
+    true => Mode::Replace,
+    false => Mode::Merge,
+  };
+  let incoming = exchange::import_file(path).await?;
+  let diff = exchange::diff(&apps, &incoming, mode)?;
+  print!("{diff}");
+  if flag("--dry-run") || diff.is_empty() {
+    return Ok(());
+  }
+  let mut apps = exchange::apply(apps, incoming, mode);
+  schedule(apps.clone())?;
+  save_all_extend_apps(&pool, &mut apps).await?;
+  println!("已{mode}导入: {path}");
+  Ok(())
+}
+
+/// 检查并预演当前工站的步骤, 存在错误时返回错误
+async fn preview(pool: &SqlitePool, sn: String) -> Result<()> {
+  let cfg = load_all_data(pool, 0).await.map_err(Error::DataParse)?;
+  let station = cfg.station_name().to_string();
+  let steps = cfg.station().map(|x| x.steps.clone()).unwrap_or_default();
+  let apps = select(cfg.apps.clone(), &steps).and_then(schedule)?;
+  let template = Template::new(
+    cfg.clone(),
+    HashMap::from([
+      ("sn".to_string(), sn),
+      ("station".to_string(), station.clone()),
+      ("operator".to_string(), cfg.user.name.clone()),
+      ("date".to_string(), "{date}".parse_format().def()),
+    ]),
+  );
+  println!("工站: {station}");
+  let report = validate(&apps);
+  for issue in report.issues.iter().chain(dry_run(&apps, &template).issues.iter()) {
+    println!("{issue}");
+  }
+  match report.has_errors() {
+    true => Err(Error::DataParse("运行前检查未通过".to_string())),
+    false => Ok(()),
+  }
+}
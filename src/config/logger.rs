@@ -285,6 +285,7 @@ pub enum Tag {
   Dev,
   Login,
   AutoHandle,
+  AutoTest,
   DataAnalysis,
   DataPrint,
   Window,
@@ -303,6 +304,7 @@ impl Display for Tag {
       Tag::LoadSetting => "加载配置",
       Tag::SaveSetting => "保存配置",
       Tag::AutoHandle => "自动监听",
+      Tag::AutoTest => "自动测试",
       Tag::DatabaseOffline => "离线数据库",
       Tag::DataAnalysis => "数据分析",
       Tag::DataPrint => "数据打印",
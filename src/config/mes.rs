@@ -0,0 +1,36 @@
+use e_utils::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// MES 上传配置
+#[derive(Serialize, Deserialize, Debug, Clone, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MesCfg {
+  pub id: i32,
+  /// 请求头, `{"名称": "值"}`
+  pub headers: Value,
+  /// 不为空时以`Authorization: Bearer <token>`发送
+  pub token: String,
+  /// 请求超时(秒)
+  pub timeout: i32,
+  /// 后台重试间隔(秒)
+  pub retry_interval: i32,
+  /// 是否上传步骤结果(`ExtendApp.res_url`), 测试结果总是上传到工站的`upload_url`
+  pub upload_steps: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+impl Default for MesCfg {
+  fn default() -> Self {
+    Self {
+      id: 0,
+      headers: Value::Object(Default::default()),
+      token: String::new(),
+      timeout: 10,
+      retry_interval: 30,
+      upload_steps: true,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    }
+  }
+}
@@ -1,19 +1,46 @@
 pub mod cargo;
 pub mod general;
 pub mod logger;
+pub mod mes;
+pub mod product;
+pub mod report;
+pub mod runner;
+pub mod station;
 pub mod theme;
-use e_utils::{parse::MyParseFormat as _, Result};
+use std::collections::HashMap;
+
+use e_utils::{
+  once_cell::sync::Lazy,
+  parse::MyParseFormat as _,
+  regex::{Captures, Regex},
+};
 
 use crate::{
-  config::logger::LogCfg,
+  config::{
+    logger::LogCfg, mes::MesCfg, product::Product, report::ReportCfg, runner::RunnerCfg, station::Station,
+  },
   data::{DB_SQLITE, ORIGIN},
   db::{
     apis::load_all_data,
-    r#type::user::{User, UserCfg, UserInfo},
+    r#type::{
+      app::ExtendApp,
+      user::{User, UserCfg, UserInfo},
+    },
   },
-  res::PanicAny,
+  res::{Error, PanicAny, Result},
 };
 
+/// `#key#`, 键可含`:`, 如`#env:VAR#`、`#step:TAG#`; `##`为字面的`#`
+static KEY_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"##|#([A-Za-z0-9_.:-]+)#").panic("KEY_PATTERN"));
+
+/// 字符串中的`#key#`, 不含转义的`##`
+pub fn keys(value: &str) -> Vec<&str> {
+  KEY_PATTERN
+    .captures_iter(value)
+    .filter_map(|caps| caps.get(1).map(|x| x.as_str()))
+    .collect()
+}
+
 /// 全局配置
 #[derive(Clone, Debug, Default)]
 pub struct Config {
@@ -22,32 +49,103 @@ pub struct Config {
   pub user_info: UserInfo,
   pub user_cfg: UserCfg,
   pub cargo: cargo::Config,
+  pub runner: RunnerCfg,
+  pub mes: MesCfg,
+  pub report: ReportCfg,
+  pub products: Vec<Product>,
+  pub stations: Vec<Station>,
+  /// 测试步骤, 按`priority`排序
+  pub apps: Vec<ExtendApp>,
 }
 
 impl Config {
   /// 初始化数据
   #[tokio::main]
-  pub async fn init_base_conf(&mut self) {
+  pub async fn init_base_conf(&mut self) -> Result<()> {
     let mut db_api = DB_SQLITE.write().await;
-    db_api.a_connect().await.panic("数据库Sqlite连接");
+    db_api.a_connect().await?;
     let pool = db_api.pool();
-    *self = load_all_data(&pool, 0).await.panic("加载数据");
-    self.log.fname = self.rkey(&self.log.fname).panic("init_base_conf");
-    self.log.folder = self.rkey(&self.log.folder).panic("init_base_conf");
+    *self = load_all_data(&pool, 0).await.map_err(|e| Error::DataParse(format!("加载数据: {e}")))?;
+    self.log.fname = self.rkey(&self.log.fname)?;
+    self.log.folder = self.rkey(&self.log.folder)?;
+    Ok(())
+  }
+
+  /// 当前产品
+  pub fn product(&self) -> Option<&Product> {
+    self.products.iter().find(|x| x.id == self.runner.product_id)
+  }
+
+  /// 当前工站
+  pub fn station(&self) -> Option<&Station> {
+    self.stations.iter().find(|x| x.id == self.runner.station_id)
   }
 
-  /// 筛选
+  /// 当前工站名称
+  pub fn station_name(&self) -> &str {
+    self.station().map(|x| x.name.as_str()).unwrap_or_default()
+  }
+
+  /// SN 校验正则, 工站配置优先于产品配置
+  pub fn sn_pattern(&self) -> Option<&str> {
+    self
+      .station()
+      .map(|x| x.sn_pattern.as_str())
+      .filter(|x| !x.is_empty())
+      .or(self.product().map(|x| x.sn_pattern.as_str()))
+  }
+
+  /// 替换`#key#`, 未知的键返回错误
   pub fn rkey<S: AsRef<str>>(&self, value: S) -> Result<String> {
-    let match_fn = |k: String| -> String {
-      match &*k {
-        "title" => self.cargo.package.description.clone(),
-        "version" => self.cargo.package.version.clone(),
-        "name" => self.cargo.package.name.clone(),
-        "origin" => ORIGIN.to_string_lossy().to_string(),
-        _ => String::new(),
-      }
-    };
-    let res = value.as_ref().parse_format()?.parse_replace('#', '#', match_fn);
-    res
+    self.rkey_with(value, &HashMap::new())
+  }
+
+  /// 替换`#key#`, 依次查找内置键、`#env:VAR#`环境变量、`vars`, 未知的键返回错误
+  ///
+  /// `##`为字面的`#`, 如地址`http://x/##a##b`; 内置键不区分大小写
+  pub fn rkey_with<S: AsRef<str>>(&self, value: S, vars: &HashMap<String, String>) -> Result<String> {
+    let value = value.as_ref().parse_format()?;
+    let mut unknown = vec![];
+    let res = KEY_PATTERN.replace_all(&value, |caps: &Captures<'_>| {
+      let Some(k) = caps.get(1).map(|x| x.as_str()) else {
+        return "#".to_string();
+      };
+      let v = match &*k.to_lowercase() {
+        "title" => Some(self.cargo.package.description.clone()),
+        "version" => Some(self.cargo.package.version.clone()),
+        "name" => Some(self.cargo.package.name.clone()),
+        "origin" => Some(ORIGIN.to_string_lossy().to_string()),
+        lower => match k.strip_prefix("env:") {
+          Some(var) => std::env::var(var).ok(),
+          None => vars.get(k).or_else(|| vars.get(lower)).cloned(),
+        },
+      };
+      v.unwrap_or_else(|| {
+        unknown.push(k.to_string());
+        String::new()
+      })
+    });
+    if !unknown.is_empty() {
+      return Err(Error::NotFound(format!("未知的变量: #{}#", unknown.join("#, #")).into()));
+    }
+    Ok(res.into_owned())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rkey_unknown_and_escape() {
+    let cfg = Config::default();
+    let vars = HashMap::from([("sn".to_string(), "A001".to_string())]);
+    assert_eq!(cfg.rkey_with("#sn#-#SN#", &vars).unwrap(), "A001-A001");
+    // 未知的键报错, 不原样保留
+    let e = cfg.rkey_with("http://x/#a#b", &vars).unwrap_err();
+    assert!(e.to_string().contains("#a#"), "{e}");
+    // `##`转义为`#`
+    assert_eq!(cfg.rkey_with("http://x/##a##b?sn=#sn#", &vars).unwrap(), "http://x/#a#b?sn=A001");
+    assert_eq!(keys("##a## #step:VOLT# #sn#"), vec!["step:VOLT", "sn"]);
   }
 }
@@ -0,0 +1,14 @@
+use e_utils::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 产品配置
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+  pub id: i32,
+  pub name: String,
+  /// SN 校验正则, 为空不校验
+  pub sn_pattern: String,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,41 @@
+use e_utils::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 测试报告配置
+#[derive(Serialize, Deserialize, Debug, Clone, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportCfg {
+  pub id: i32,
+  /// 报告目录, 支持`Config::rkey`的键
+  pub folder: String,
+  /// 文件名(不含扩展名), 另支持`#sn#`、`#station#`、`#result#`、`#timestamp#`等运行变量
+  pub fname: String,
+  pub html: bool,
+  /// JUnit XML, 供 CI 看板导入
+  pub junit: bool,
+  /// 每个步骤一行
+  pub csv: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+impl Default for ReportCfg {
+  fn default() -> Self {
+    Self {
+      id: 0,
+      folder: "#origin#/reports".to_string(),
+      fname: "#sn#_#station#_#timestamp#".to_string(),
+      html: true,
+      junit: true,
+      csv: true,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    }
+  }
+}
+
+impl ReportCfg {
+  /// 是否生成报告
+  pub fn enabled(&self) -> bool {
+    self.html || self.junit || self.csv
+  }
+}
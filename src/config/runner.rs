@@ -0,0 +1,29 @@
+use e_utils::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 测试执行配置(工站级)
+#[derive(Serialize, Deserialize, Debug, Clone, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerCfg {
+  pub id: i32,
+  /// 不等待(`is_wait = false`)步骤的最大并行数
+  pub max_parallel: i32,
+  /// 当前产品 `Product.id`
+  pub product_id: i32,
+  /// 当前工站 `Station.id`
+  pub station_id: i32,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+impl Default for RunnerCfg {
+  fn default() -> Self {
+    Self {
+      id: 0,
+      max_parallel: 4,
+      product_id: 0,
+      station_id: 0,
+      created_at: Default::default(),
+      updated_at: Default::default(),
+    }
+  }
+}
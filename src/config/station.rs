@@ -0,0 +1,61 @@
+use e_utils::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(sqlx::FromRow)]
+pub struct StationQ {
+  pub id: i32,
+  pub name: String,
+  pub steps: Value,
+  pub sn_pattern: String,
+  pub upload_url: String,
+  pub pre_hook: String,
+  pub post_hook: String,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+/// 工站配置
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Station {
+  pub id: i32,
+  pub name: String,
+  /// 执行的步骤`tag`及顺序, 为空执行全部已启用步骤
+  #[serde(default)]
+  pub steps: Vec<String>,
+  /// SN 校验正则, 为空使用产品配置
+  #[serde(default)]
+  pub sn_pattern: String,
+  /// 测试结果上传地址
+  #[serde(default)]
+  pub upload_url: String,
+  /// 测试开始前执行的脚本, 失败时不执行步骤
+  #[serde(default)]
+  pub pre_hook: String,
+  /// 测试结束后执行的脚本, 可引用`#result#`
+  #[serde(default)]
+  pub post_hook: String,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
+
+impl From<StationQ> for Station {
+  fn from(value: StationQ) -> Self {
+    Self {
+      id: value.id,
+      name: value.name,
+      steps: value
+        .steps
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default(),
+      sn_pattern: value.sn_pattern,
+      upload_url: value.upload_url,
+      pre_hook: value.pre_hook,
+      post_hook: value.post_hook,
+      created_at: value.created_at,
+      updated_at: value.updated_at,
+    }
+  }
+}
@@ -6,8 +6,8 @@ pub async fn insert_extend_app(pool: &SqlitePool, app: ExtendApp) -> sqlx::Resul
   let app = ExtendAppQ::from(app);
   let res = sqlx::query(
         r#"
-        INSERT INTO externApp (tag, label, enable, type, priority, is_check, is_repeat, is_wait, timeout, count, cmd, cwd, res_url, filter)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+        INSERT INTO externApp (tag, label, enable, type, priority, is_check, is_repeat, is_wait, timeout, count, cmd, cwd, res_url, filter, limits, depends_on, retry, capture, condition, params)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
         "#,
     )
     .bind(app.tag)
@@ -24,6 +24,12 @@ pub async fn insert_extend_app(pool: &SqlitePool, app: ExtendApp) -> sqlx::Resul
     .bind(app.cwd)
     .bind(app.res_url)
     .bind(app.filter)
+    .bind(app.limits)
+    .bind(app.depends_on)
+    .bind(app.retry)
+    .bind(app.capture)
+    .bind(app.condition)
+    .bind(app.params)
     .execute(pool)
     .await?;
 
@@ -46,8 +52,10 @@ pub async fn update_extend_app(pool: &SqlitePool, app: ExtendApp) -> sqlx::Resul
         r#"
         UPDATE externApp
         SET tag = ?1, label = ?2, enable = ?3, type = ?4, priority = ?5, is_check = ?6, is_repeat = ?7, is_wait = ?8, 
-            timeout = ?9, count = ?10, cmd = ?11, cwd = ?12, res_url = ?13, filter = ?14, updated_at = CURRENT_TIMESTAMP
-        WHERE id = ?15
+            timeout = ?9, count = ?10, cmd = ?11, cwd = ?12, res_url = ?13, filter = ?14, limits = ?15,
+            depends_on = ?16, retry = ?17, capture = ?18, condition = ?19,
+            params = ?20, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?21
         "#,
     )
     .bind(app.tag)
@@ -64,6 +72,12 @@ pub async fn update_extend_app(pool: &SqlitePool, app: ExtendApp) -> sqlx::Resul
     .bind(app.cwd)
     .bind(app.res_url)
     .bind(app.filter)
+    .bind(app.limits)
+    .bind(app.depends_on)
+    .bind(app.retry)
+    .bind(app.capture)
+    .bind(app.condition)
+    .bind(app.params)
     .bind(app.id)
     .execute(pool)
     .await?;
@@ -98,3 +112,38 @@ pub async fn select_all_extend_apps(pool: &SqlitePool) -> sqlx::Result<Vec<Exten
     .await
     .map(|apps| apps.into_iter().map(ExtendApp::from).collect())
 }
+
+/// 保存全部扩展应用: 删除不在列表中的, `id = 0`的插入并回填`id`, 其余更新
+pub async fn save_all_extend_apps(pool: &SqlitePool, apps: &mut [ExtendApp]) -> sqlx::Result<()> {
+  let ids: Vec<i32> = query_scalar("SELECT id FROM externApp").fetch_all(pool).await?;
+  for id in ids.into_iter().filter(|id| apps.iter().all(|x| x.id != *id)) {
+    delete_extend_app(pool, id).await?;
+  }
+  for app in apps.iter_mut() {
+    if app.id == 0 {
+      app.id = insert_extend_app(pool, app.clone()).await? as i32;
+    } else {
+      update_extend_app(pool, app.clone()).await?;
+    }
+  }
+  Ok(())
+}
+
+/// 保存步骤编辑: 与编辑前的`base`比较, 只删除编辑中删除的、插入新增的、更新修改过的;
+/// 其余步骤(如编辑期间由命令行导入的)保持数据库中的内容
+pub async fn save_extend_apps(pool: &SqlitePool, base: &[ExtendApp], apps: &mut [ExtendApp]) -> sqlx::Result<()> {
+  let content = |app: &ExtendApp| serde_json::to_value(app).ok();
+  for old in base.iter().filter(|old| apps.iter().all(|x| x.id != old.id)) {
+    delete_extend_app(pool, old.id).await?;
+  }
+  for app in apps.iter_mut() {
+    match base.iter().find(|x| x.id == app.id) {
+      _ if app.id == 0 => app.id = insert_extend_app(pool, app.clone()).await? as i32,
+      Some(old) if content(old) == content(app) => {}
+      _ => {
+        update_extend_app(pool, app.clone()).await?;
+      }
+    }
+  }
+  Ok(())
+}
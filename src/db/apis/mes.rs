@@ -0,0 +1,32 @@
+use sqlx::{query_as, sqlite::SqliteQueryResult, SqlitePool};
+
+use crate::config::mes::MesCfg;
+
+/// 获取MES上传配置
+pub async fn select_mes(pool: &SqlitePool, id: i64) -> sqlx::Result<MesCfg> {
+  query_as::<_, MesCfg>("SELECT * from mes WHERE (id=?)")
+    .bind(id)
+    .fetch_one(pool)
+    .await
+}
+
+/// 更新MES上传配置
+pub async fn update_mes(pool: &SqlitePool, cfg: &MesCfg) -> sqlx::Result<SqliteQueryResult> {
+  let res = sqlx::query(
+    r#"
+        UPDATE mes
+        SET headers = ?1, token = ?2, timeout = ?3, retry_interval = ?4, upload_steps = ?5,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?6
+        "#,
+  )
+  .bind(&cfg.headers)
+  .bind(&cfg.token)
+  .bind(cfg.timeout)
+  .bind(cfg.retry_interval)
+  .bind(cfg.upload_steps)
+  .bind(cfg.id)
+  .execute(pool)
+  .await?;
+  Ok(res)
+}
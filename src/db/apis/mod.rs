@@ -1,5 +1,11 @@
-use crate::config::Config;
+use crate::{config::Config, db::r#type::app::ExtendApp};
+use app::{save_extend_apps, select_all_extend_apps};
 use log::{select_log, update_log};
+use mes::{select_mes, update_mes};
+use product::{select_all_products, update_product};
+use report::{select_report, update_report};
+use runner::{select_runner, update_runner};
+use station::{select_all_stations, update_station};
 use sqlx::SqlitePool;
 use user::{
   select_user, select_user_cfg, select_user_info, update_user, update_user_cfg, update_user_info,
@@ -7,6 +13,13 @@ use user::{
 };
 pub mod app;
 pub mod log;
+pub mod mes;
+pub mod outbox;
+pub mod product;
+pub mod report;
+pub mod run;
+pub mod runner;
+pub mod station;
 pub mod user;
 /// sqlite3
 pub async fn load_all_data(pool: &SqlitePool, id: i64) -> Result<Config, String> {
@@ -16,14 +29,23 @@ pub async fn load_all_data(pool: &SqlitePool, id: i64) -> Result<Config, String>
     slf.user_cfg = select_user_cfg(pool, id).await?;
     slf.user_info = select_user_info(pool, id).await?;
     slf.log = select_log(pool, id).await?;
+    slf.runner = select_runner(pool, id).await?;
+    slf.mes = select_mes(pool, id).await?;
+    slf.report = select_report(pool, id).await?;
+    slf.products = select_all_products(pool).await?;
+    slf.stations = select_all_stations(pool).await?;
+    slf.apps = select_all_extend_apps(pool).await?;
+    slf.apps.sort_by_key(|x| x.priority);
     Ok(slf)
   }
   f(pool, id).await.map_err(|e| e.to_string())
 }
 
 /// sqlite3
-pub async fn save_all_data(pool: &SqlitePool, slf: Config) -> Result<Config, String> {
-  async fn f(pool: &SqlitePool, slf: Config) -> sqlx::Result<Config> {
+///
+/// 步骤只写入相对编辑前`base`的改动(新增、修改、删除), 保存后重新读取
+pub async fn save_all_data(pool: &SqlitePool, slf: Config, base: Vec<ExtendApp>) -> Result<Config, String> {
+  async fn f(pool: &SqlitePool, mut slf: Config, base: Vec<ExtendApp>) -> sqlx::Result<Config> {
     if slf.user.password.is_empty() {
       update_user(pool, &slf.user).await?;
     } else {
@@ -32,7 +54,19 @@ pub async fn save_all_data(pool: &SqlitePool, slf: Config) -> Result<Config, Str
     update_user_info(pool, &slf.user_info).await?;
     update_user_cfg(pool, slf.user_cfg.clone()).await?;
     update_log(pool, slf.log.clone()).await?;
+    update_runner(pool, &slf.runner).await?;
+    update_mes(pool, &slf.mes).await?;
+    update_report(pool, &slf.report).await?;
+    for product in &slf.products {
+      update_product(pool, product).await?;
+    }
+    for station in &slf.stations {
+      update_station(pool, station).await?;
+    }
+    save_extend_apps(pool, &base, &mut slf.apps).await?;
+    slf.apps = select_all_extend_apps(pool).await?;
+    slf.apps.sort_by_key(|x| x.priority);
     Ok(slf)
   }
-  f(pool, slf).await.map_err(|e| e.to_string())
+  f(pool, slf, base).await.map_err(|e| e.to_string())
 }
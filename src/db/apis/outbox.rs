@@ -0,0 +1,79 @@
+use e_utils::time::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{query_as, query_scalar, SqlitePool};
+
+use crate::db::r#type::outbox::OutboxItem;
+
+/// 加入待上传队列
+pub async fn insert_outbox(pool: &SqlitePool, url: &str, payload: &Value) -> sqlx::Result<i64> {
+  let res = sqlx::query("INSERT INTO outbox (url, payload) VALUES (?1, ?2)")
+    .bind(url)
+    .bind(payload)
+    .execute(pool)
+    .await?;
+  Ok(res.last_insert_rowid())
+}
+
+/// 按加入顺序获取已到发送时间的待上传结果
+pub async fn select_outbox(pool: &SqlitePool, now: DateTime<Utc>, limit: i64) -> sqlx::Result<Vec<OutboxItem>> {
+  query_as::<_, OutboxItem>(
+    "SELECT * FROM outbox WHERE dead = 0 AND (next_at IS NULL OR next_at <= ?1) ORDER BY id LIMIT ?2",
+  )
+  .bind(now)
+  .bind(limit)
+  .fetch_all(pool)
+  .await
+}
+
+/// 上传成功后删除
+pub async fn delete_outbox(pool: &SqlitePool, id: i64) -> sqlx::Result<i64> {
+  let res = sqlx::query("DELETE FROM outbox WHERE id = ?")
+    .bind(id)
+    .execute(pool)
+    .await?;
+  Ok(res.rows_affected() as i64)
+}
+
+/// 记录上传失败, `next_at`后重试; `dead`时不再发送
+pub async fn fail_outbox(
+  pool: &SqlitePool,
+  id: i64,
+  error: &str,
+  next_at: DateTime<Utc>,
+  dead: bool,
+) -> sqlx::Result<i64> {
+  let res = sqlx::query(
+    r#"
+        UPDATE outbox SET attempts = attempts + 1, last_error = ?1, next_at = ?2, dead = ?3,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?4
+        "#,
+  )
+  .bind(error)
+  .bind(next_at)
+  .bind(dead)
+  .bind(id)
+  .execute(pool)
+  .await?;
+  Ok(res.rows_affected() as i64)
+}
+
+/// 已放弃的结果重新加入队列, 立即发送
+pub async fn requeue_outbox(pool: &SqlitePool) -> sqlx::Result<i64> {
+  let res = sqlx::query(
+    "UPDATE outbox SET dead = 0, attempts = 0, next_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE dead = 1",
+  )
+  .execute(pool)
+  .await?;
+  Ok(res.rows_affected() as i64)
+}
+
+/// 待上传数量, 不含已放弃的
+pub async fn count_outbox(pool: &SqlitePool) -> sqlx::Result<i64> {
+  query_scalar("SELECT COUNT(*) FROM outbox WHERE dead = 0").fetch_one(pool).await
+}
+
+/// 已放弃上传的数量
+pub async fn count_dead_outbox(pool: &SqlitePool) -> sqlx::Result<i64> {
+  query_scalar("SELECT COUNT(*) FROM outbox WHERE dead = 1").fetch_one(pool).await
+}
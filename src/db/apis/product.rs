@@ -0,0 +1,27 @@
+use sqlx::{query_as, sqlite::SqliteQueryResult, SqlitePool};
+
+use crate::config::product::Product;
+
+/// 获取所有产品
+pub async fn select_all_products(pool: &SqlitePool) -> sqlx::Result<Vec<Product>> {
+  query_as::<_, Product>("SELECT * FROM product ORDER BY id")
+    .fetch_all(pool)
+    .await
+}
+
+/// 更新产品
+pub async fn update_product(pool: &SqlitePool, v: &Product) -> sqlx::Result<SqliteQueryResult> {
+  let res = sqlx::query(
+    r#"
+        UPDATE product
+        SET name = ?1, sn_pattern = ?2, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?3
+        "#,
+  )
+  .bind(&v.name)
+  .bind(&v.sn_pattern)
+  .bind(v.id)
+  .execute(pool)
+  .await?;
+  Ok(res)
+}
@@ -0,0 +1,31 @@
+use sqlx::{query_as, sqlite::SqliteQueryResult, SqlitePool};
+
+use crate::config::report::ReportCfg;
+
+/// 获取测试报告配置
+pub async fn select_report(pool: &SqlitePool, id: i64) -> sqlx::Result<ReportCfg> {
+  query_as::<_, ReportCfg>("SELECT * from report WHERE (id=?)")
+    .bind(id)
+    .fetch_one(pool)
+    .await
+}
+
+/// 更新测试报告配置
+pub async fn update_report(pool: &SqlitePool, cfg: &ReportCfg) -> sqlx::Result<SqliteQueryResult> {
+  let res = sqlx::query(
+    r#"
+        UPDATE report
+        SET folder = ?1, fname = ?2, html = ?3, junit = ?4, csv = ?5, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?6
+        "#,
+  )
+  .bind(&cfg.folder)
+  .bind(&cfg.fname)
+  .bind(cfg.html)
+  .bind(cfg.junit)
+  .bind(cfg.csv)
+  .bind(cfg.id)
+  .execute(pool)
+  .await?;
+  Ok(res)
+}
@@ -0,0 +1,187 @@
+use e_utils::time::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{query_as, query_scalar, QueryBuilder, Sqlite, SqlitePool};
+
+use crate::db::r#type::run::{RunQuery, TestRun, TestStepResult};
+
+/// 插入测试记录
+pub async fn insert_run(pool: &SqlitePool, run: &TestRun) -> sqlx::Result<i64> {
+  let res = sqlx::query(
+    r#"
+        INSERT INTO test_run (sn, station, user_id, result, started_at, ended_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+  )
+  .bind(&run.sn)
+  .bind(&run.station)
+  .bind(run.user_id)
+  .bind(&run.result)
+  .bind(run.started_at)
+  .bind(run.ended_at)
+  .execute(pool)
+  .await?;
+  Ok(res.last_insert_rowid())
+}
+
+/// 结束测试记录
+pub async fn finish_run(
+  pool: &SqlitePool,
+  id: i64,
+  result: &str,
+  ended_at: DateTime<Utc>,
+  vars: &Value,
+) -> sqlx::Result<i64> {
+  let res = sqlx::query("UPDATE test_run SET result = ?1, ended_at = ?2, vars = ?3 WHERE id = ?4")
+    .bind(result)
+    .bind(ended_at)
+    .bind(vars)
+    .bind(id)
+    .execute(pool)
+    .await?;
+  Ok(res.rows_affected() as i64)
+}
+
+/// 插入步骤记录
+pub async fn insert_step_result(pool: &SqlitePool, v: &TestStepResult) -> sqlx::Result<i64> {
+  let res = sqlx::query(
+    r#"
+        INSERT INTO test_step_result (run_id, app_id, tag, label, attempt, state, stdout, stderr,
+            res_value, limits, violation, duration_ms)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        "#,
+  )
+  .bind(v.run_id)
+  .bind(v.app_id)
+  .bind(&v.tag)
+  .bind(&v.label)
+  .bind(v.attempt)
+  .bind(&v.state)
+  .bind(&v.stdout)
+  .bind(&v.stderr)
+  .bind(&v.res_value)
+  .bind(&v.limits)
+  .bind(&v.violation)
+  .bind(v.duration_ms)
+  .execute(pool)
+  .await?;
+  Ok(res.last_insert_rowid())
+}
+
+/// 获取测试记录
+pub async fn select_run(pool: &SqlitePool, id: i64) -> sqlx::Result<TestRun> {
+  query_as::<_, TestRun>("SELECT * FROM test_run WHERE id = ?")
+    .bind(id)
+    .fetch_one(pool)
+    .await
+}
+
+/// 获取测试记录的步骤记录
+pub async fn select_step_results(pool: &SqlitePool, run_id: i64) -> sqlx::Result<Vec<TestStepResult>> {
+  query_as::<_, TestStepResult>("SELECT * FROM test_step_result WHERE run_id = ? ORDER BY id")
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// 步骤`s`的最后一次执行失败
+const FINAL_FAILED: &str = "s.state IN ('Fail', 'Timeout') AND s.attempt = \
+  (SELECT MAX(attempt) FROM test_step_result WHERE run_id = s.run_id AND tag = s.tag)";
+
+/// 拼接查询条件
+fn push_filter<'a>(builder: &mut QueryBuilder<'a, Sqlite>, q: &'a RunQuery) {
+  builder.push(" WHERE 1 = 1");
+  if let Some(sn) = q.sn.as_ref().filter(|x| !x.is_empty()) {
+    builder.push(" AND sn LIKE ").push_bind(format!("%{sn}%"));
+  }
+  if let Some(station) = q.station.as_ref().filter(|x| !x.is_empty()) {
+    builder.push(" AND station = ").push_bind(station);
+  }
+  if let Some(user_id) = q.user_id {
+    builder.push(" AND user_id = ").push_bind(user_id);
+  }
+  if let Some(result) = q.result.as_ref().filter(|x| !x.is_empty()) {
+    builder.push(" AND result = ").push_bind(result);
+  }
+  if let Some(start) = q.start {
+    builder.push(" AND started_at >= ").push_bind(start);
+  }
+  if let Some(end) = q.end {
+    builder.push(" AND started_at < ").push_bind(end);
+  }
+  if let Some(tag) = q.failed_step.as_ref().filter(|x| !x.is_empty()) {
+    builder
+      .push(" AND test_run.id IN (SELECT s.run_id FROM test_step_result s WHERE s.tag = ")
+      .push_bind(tag)
+      .push(" AND ")
+      .push(FINAL_FAILED)
+      .push(")");
+  }
+}
+
+/// 按条件分页查询测试记录, 最新的在前
+pub async fn select_runs(pool: &SqlitePool, q: &RunQuery) -> sqlx::Result<Vec<TestRun>> {
+  let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM test_run");
+  push_filter(&mut builder, q);
+  builder
+    .push(" ORDER BY started_at DESC, id DESC LIMIT ")
+    .push_bind(q.limit)
+    .push(" OFFSET ")
+    .push_bind(q.offset);
+  builder.build_query_as::<TestRun>().fetch_all(pool).await
+}
+
+/// 按条件统计测试记录数量
+pub async fn count_runs(pool: &SqlitePool, q: &RunQuery) -> sqlx::Result<i64> {
+  let mut builder = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM test_run");
+  push_filter(&mut builder, q);
+  builder.build_query_scalar::<i64>().fetch_one(pool).await
+}
+
+/// 按条件统计最终失败次数最多的步骤, 返回`(tag, 失败的测试记录数)`
+pub async fn count_failed_steps(pool: &SqlitePool, q: &RunQuery, limit: i64) -> sqlx::Result<Vec<(String, i64)>> {
+  let mut builder = QueryBuilder::<Sqlite>::new(
+    "SELECT s.tag, COUNT(DISTINCT s.run_id) AS n FROM test_step_result s JOIN test_run ON test_run.id = s.run_id",
+  );
+  push_filter(&mut builder, q);
+  builder
+    .push(" AND ")
+    .push(FINAL_FAILED)
+    .push(" GROUP BY s.tag ORDER BY n DESC, s.tag LIMIT ")
+    .push_bind(limit);
+  builder.build_query_as::<(String, i64)>().fetch_all(pool).await
+}
+
+/// 步骤最近`limit`次测试的最终执行记录(通过或失败), 按时间正序; `station`为空时不限工站
+pub async fn select_step_history(
+  pool: &SqlitePool,
+  tag: &str,
+  station: &str,
+  limit: i64,
+) -> sqlx::Result<Vec<TestStepResult>> {
+  let mut rows = query_as::<_, TestStepResult>(
+    r#"
+        SELECT s.* FROM test_step_result s JOIN test_run ON test_run.id = s.run_id
+        WHERE s.tag = ?1 AND (?2 = '' OR test_run.station = ?2) AND s.state IN ('Pass', 'Fail')
+            AND s.attempt = (SELECT MAX(attempt) FROM test_step_result WHERE run_id = s.run_id AND tag = s.tag)
+        ORDER BY test_run.started_at DESC, s.id DESC LIMIT ?3
+        "#,
+  )
+  .bind(tag)
+  .bind(station)
+  .bind(limit)
+  .fetch_all(pool)
+  .await?;
+  rows.reverse();
+  Ok(rows)
+}
+
+/// 序列号在工站是否已有指定结果的记录
+pub async fn exist_run_result(pool: &SqlitePool, sn: &str, station: &str, result: &str) -> sqlx::Result<bool> {
+  let res: i64 = query_scalar("SELECT COUNT(*) FROM test_run WHERE sn = ?1 AND station = ?2 AND result = ?3")
+    .bind(sn)
+    .bind(station)
+    .bind(result)
+    .fetch_one(pool)
+    .await?;
+  Ok(res > 0)
+}
@@ -0,0 +1,28 @@
+use sqlx::{query_as, sqlite::SqliteQueryResult, SqlitePool};
+
+use crate::config::runner::RunnerCfg;
+/// 获取测试执行配置
+pub async fn select_runner(pool: &SqlitePool, id: i64) -> sqlx::Result<RunnerCfg> {
+  query_as::<_, RunnerCfg>("SELECT * from runner WHERE (id=?)")
+    .bind(id)
+    .fetch_one(pool)
+    .await
+}
+
+/// 更新测试执行配置
+pub async fn update_runner(pool: &SqlitePool, cfg: &RunnerCfg) -> sqlx::Result<SqliteQueryResult> {
+  let res = sqlx::query(
+    r#"
+        UPDATE runner
+        SET max_parallel = ?1, product_id = ?2, station_id = ?3, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?4
+        "#,
+  )
+  .bind(cfg.max_parallel)
+  .bind(cfg.product_id)
+  .bind(cfg.station_id)
+  .bind(cfg.id)
+  .execute(pool)
+  .await?;
+  Ok(res)
+}
@@ -0,0 +1,35 @@
+use sqlx::{query_as, sqlite::SqliteQueryResult, SqlitePool};
+
+use crate::config::station::{Station, StationQ};
+
+/// 获取所有工站
+pub async fn select_all_stations(pool: &SqlitePool) -> sqlx::Result<Vec<Station>> {
+  let res = query_as::<_, StationQ>("SELECT * FROM station ORDER BY id")
+    .fetch_all(pool)
+    .await?;
+  Ok(res.into_iter().map(Station::from).collect())
+}
+
+/// 更新工站
+pub async fn update_station(pool: &SqlitePool, v: &Station) -> sqlx::Result<SqliteQueryResult> {
+  let res = sqlx::query(
+    r#"
+        UPDATE station
+        SET name = ?1, steps = ?2, sn_pattern = ?3, upload_url = ?4, pre_hook = ?5, post_hook = ?6,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = ?7
+        "#,
+  )
+  .bind(&v.name)
+  .bind(serde_json::Value::Array(
+    v.steps.iter().cloned().map(serde_json::Value::String).collect(),
+  ))
+  .bind(&v.sn_pattern)
+  .bind(&v.upload_url)
+  .bind(&v.pre_hook)
+  .bind(&v.post_hook)
+  .bind(v.id)
+  .execute(pool)
+  .await?;
+  Ok(res)
+}
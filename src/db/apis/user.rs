@@ -10,6 +10,12 @@ pub async fn select_user(pool: &SqlitePool, id: i64) -> sqlx::Result<User> {
   user.password = "".to_string();
   Ok(user)
 }
+/// 获取全部用户, 不含密码
+pub async fn select_all_users(pool: &SqlitePool) -> sqlx::Result<Vec<User>> {
+  let mut users = query_as::<_, User>("SELECT * from  user ORDER BY id").fetch_all(pool).await?;
+  users.iter_mut().for_each(|x| x.password.clear());
+  Ok(users)
+}
 /// 获取用户配置
 pub async fn select_user_cfg(pool: &SqlitePool, id: i64) -> sqlx::Result<UserCfg> {
   query_as::<_, UserCfgQ>("SELECT * from  user_cfg WHERE (user_id=?)")
@@ -8,7 +8,7 @@ use sqlx::{
   ConnectOptions as _, Connection as _, Error, Row, Sqlite,
 };
 
-use super::{migrate_run, ManageConn};
+use super::{get_migrator, migrate_run, ManageConn};
 use crate::{
   config::logger::{log, Tag},
   res::Result,
@@ -39,7 +39,12 @@ impl DbClient {
     {
       Ok(x) => {
         let count = x.get::<i64, &str>("count");
-        if count < 2 {
+        let total = get_migrator(migrate_workspace)
+          .await?
+          .iter()
+          .filter(|m| m.migration_type.is_up_migration())
+          .count() as i64;
+        if count < total {
           migrate_run(migrate_workspace, pool).await?;
         }
       }
@@ -2,6 +2,8 @@ use e_utils::time::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::plugins::{condition::Condition, limit::Limit, retry::Retry, step::StepKind};
+
 #[derive(sqlx::FromRow)]
 pub struct ExtendAppQ {
   pub id: i32,
@@ -19,6 +21,12 @@ pub struct ExtendAppQ {
   pub cwd: Option<String>,
   pub res_url: Option<String>,
   pub filter: Value,
+  pub limits: Value,
+  pub depends_on: Value,
+  pub retry: Value,
+  pub capture: Option<String>,
+  pub condition: Value,
+  pub params: Value,
   pub created_at: DateTime<Utc>,
   pub updated_at: DateTime<Utc>,
 }
@@ -26,11 +34,15 @@ pub struct ExtendAppQ {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ExtendApp {
+  /// 导入时缺省为0, 表示新步骤
+  #[serde(default)]
   pub id: i32,
   pub tag: String,
   pub label: String,
   pub enable: bool,
-  pub r#type: i32,
+  /// 步骤类型及参数
+  #[serde(default)]
+  pub r#type: StepKind,
   pub priority: i32,
   pub is_check: bool,
   pub is_repeat: bool,
@@ -41,7 +53,21 @@ pub struct ExtendApp {
   pub cwd: Option<String>,
   pub res_url: Option<String>,
   pub filter: Vec<String>,
+  #[serde(default)]
+  pub limits: Limit,
+  /// 前置步骤的`tag`
+  #[serde(default)]
+  pub depends_on: Vec<String>,
+  #[serde(default)]
+  pub retry: Retry,
+  /// 通过时将结果值保存为变量, 后续步骤以`#name#`引用
+  #[serde(default)]
+  pub capture: Option<String>,
+  #[serde(default)]
+  pub condition: Condition,
+  #[serde(default)]
   pub created_at: DateTime<Utc>,
+  #[serde(default)]
   pub updated_at: DateTime<Utc>,
 }
 
@@ -52,7 +78,7 @@ impl From<ExtendAppQ> for ExtendApp {
       tag: value.tag,
       label: value.label,
       enable: value.enable,
-      r#type: value.r#type,
+      r#type: StepKind::from_db(value.r#type, value.params),
       priority: value.priority,
       is_check: value.is_check,
       is_repeat: value.is_repeat,
@@ -71,6 +97,15 @@ impl From<ExtendAppQ> for ExtendApp {
         .as_array()
         .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
         .unwrap_or_default(),
+      limits: serde_json::from_value(value.limits).unwrap_or_default(),
+      depends_on: value
+        .depends_on
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default(),
+      retry: serde_json::from_value(value.retry).unwrap_or_default(),
+      capture: value.capture,
+      condition: serde_json::from_value(value.condition).unwrap_or_default(),
       created_at: value.created_at,
       updated_at: value.updated_at,
     }
@@ -83,7 +118,7 @@ impl From<ExtendApp> for ExtendAppQ {
       tag: value.tag,
       label: value.label,
       enable: value.enable,
-      r#type: value.r#type,
+      r#type: value.r#type.code(),
       priority: value.priority,
       is_check: value.is_check,
       is_repeat: value.is_repeat,
@@ -94,6 +129,12 @@ impl From<ExtendApp> for ExtendAppQ {
       cwd: value.cwd,
       res_url: value.res_url,
       filter: serde_json::Value::Array(value.filter.into_iter().map(serde_json::Value::String).collect()),
+      limits: serde_json::to_value(value.limits).unwrap_or_default(),
+      depends_on: serde_json::Value::Array(value.depends_on.into_iter().map(serde_json::Value::String).collect()),
+      retry: serde_json::to_value(value.retry).unwrap_or_default(),
+      capture: value.capture,
+      condition: serde_json::to_value(value.condition).unwrap_or_default(),
+      params: serde_json::to_value(&value.r#type).unwrap_or_default(),
       created_at: value.created_at,
       updated_at: value.updated_at,
     }
@@ -1,3 +1,5 @@
 pub mod app;
 pub mod logger;
+pub mod outbox;
+pub mod run;
 pub mod user;
@@ -0,0 +1,21 @@
+use e_utils::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 待上传的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxItem {
+  pub id: i64,
+  pub url: String,
+  pub payload: Value,
+  /// 已尝试次数
+  pub attempts: i32,
+  pub last_error: Option<String>,
+  /// 下次发送时间, 为空时立即发送
+  pub next_at: Option<DateTime<Utc>>,
+  /// 超过重试次数或被拒绝(4xx), 不再发送
+  pub dead: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: DateTime<Utc>,
+}
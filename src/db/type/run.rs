@@ -0,0 +1,73 @@
+use e_utils::time::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 测试记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRun {
+  pub id: i64,
+  pub sn: String,
+  pub station: String,
+  /// 操作员 `User.id`
+  pub user_id: i32,
+  /// 总结果, 同`DataState::as_str`
+  pub result: String,
+  pub started_at: DateTime<Utc>,
+  pub ended_at: Option<DateTime<Utc>>,
+  /// 运行变量, 含步骤捕获的结果值
+  pub vars: Value,
+}
+
+/// 步骤记录, 每次执行一行
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TestStepResult {
+  pub id: i64,
+  pub run_id: i64,
+  /// 执行时的 `ExtendApp.id`
+  pub app_id: i32,
+  pub tag: String,
+  pub label: String,
+  pub attempt: i32,
+  pub state: String,
+  pub stdout: String,
+  pub stderr: String,
+  pub res_value: Value,
+  pub limits: Value,
+  pub violation: Option<String>,
+  pub duration_ms: i64,
+  pub created_at: DateTime<Utc>,
+}
+
+/// 测试记录查询条件, 为空的条件不过滤
+#[derive(Debug, Clone)]
+pub struct RunQuery {
+  /// 模糊匹配
+  pub sn: Option<String>,
+  pub station: Option<String>,
+  pub user_id: Option<i32>,
+  pub result: Option<String>,
+  pub start: Option<DateTime<Utc>>,
+  pub end: Option<DateTime<Utc>>,
+  /// 该步骤最终失败(`Fail`/`Timeout`)
+  pub failed_step: Option<String>,
+  /// SQLite 中负数表示不限制
+  pub limit: i64,
+  pub offset: i64,
+}
+impl Default for RunQuery {
+  fn default() -> Self {
+    Self {
+      sn: None,
+      station: None,
+      user_id: None,
+      result: None,
+      start: None,
+      end: None,
+      failed_step: None,
+      limit: 50,
+      offset: 0,
+    }
+  }
+}
@@ -17,6 +17,12 @@ pub enum Event {
   Load,
   ChangeBit,
   Focused(window::Id),
+  /// 开始测试
+  Start,
+  /// 暂停/继续测试
+  Pause,
+  /// 单步测试
+  Step,
 }
 
 pub fn events() -> Subscription<Event> {
@@ -36,6 +42,9 @@ pub fn filter(event: iced::Event, status: event::Status) -> Option<Event> {
         }
       }
       keyboard::Key::Named(Named::Tab) => Some(Event::ChangeBit),
+      keyboard::Key::Named(Named::F5) if ignored(status) => Some(Event::Start),
+      keyboard::Key::Named(Named::F6) if ignored(status) => Some(Event::Pause),
+      keyboard::Key::Named(Named::F10) if ignored(status) => Some(Event::Step),
       // CTRL + S or ⌘ + S saves the current configuration
       keyboard::Key::Character("s") if modifiers.command() => Some(Event::Save),
       // CTRL + M or ⌘ + M loads the current configuration
@@ -16,6 +16,7 @@
 #[macro_use]
 #[path = "macros.rs"]
 mod macros;
+mod cli;
 mod config;
 mod data;
 mod db;
@@ -29,6 +30,9 @@ use ui::main_app;
 
 // #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
 fn main() -> Result<()> {
+  if let Some(res) = cli::run() {
+    return res;
+  }
   let _ = main_app::App::launch()?;
   Ok(())
 }
@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  res::{Error, Result},
+  ui::main_app::autotest::DataState,
+};
+
+/// 执行条件, 存储于`externApp.condition`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Condition {
+  pub when: When,
+  /// 总是执行(清理、复位步骤): 前置步骤失败或运行中止后仍然执行, 不受中止影响, 仍受超时限制
+  pub always: bool,
+}
+
+/// 满足时才执行, 否则跳过
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum When {
+  /// 无条件
+  #[default]
+  None,
+  /// 指定步骤通过
+  Passed { tag: String },
+  /// 指定步骤失败或超时
+  Failed { tag: String },
+  /// 变量表达式为真, 如 `#mode# == "full"`、`#step:VOLT# > 3.3`、`#flag#`
+  Expr { expr: String },
+}
+
+impl When {
+  /// 条件引用的步骤
+  pub fn tag(&self) -> Option<&str> {
+    match self {
+      When::Passed { tag } | When::Failed { tag } => Some(tag),
+      _ => None,
+    }
+  }
+
+  /// 引用步骤结束后判定
+  pub fn matches(&self, state: DataState) -> bool {
+    match self {
+      When::Passed { .. } => state == DataState::Success,
+      When::Failed { .. } => matches!(state, DataState::Fail | DataState::Timeout),
+      _ => true,
+    }
+  }
+}
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+  Eq,
+  Ne,
+  Ge,
+  Le,
+  Gt,
+  Lt,
+}
+impl Op {
+  /// 按长度匹配, 先匹配双字符
+  const ALL: [(&'static str, Op); 6] = [
+    ("==", Op::Eq),
+    ("!=", Op::Ne),
+    (">=", Op::Ge),
+    ("<=", Op::Le),
+    (">", Op::Gt),
+    ("<", Op::Lt),
+  ];
+}
+
+/// 变量表达式 `左值 [运算符 右值]`, 只有左值时按真值判定
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+  lhs: String,
+  rhs: Option<(Op, String)>,
+}
+
+/// 去掉首尾空白和成对的引号
+fn operand(s: &str) -> Result<String> {
+  let s = s.trim();
+  let unquoted = ['"', '\''].iter().find_map(|q| s.strip_prefix(*q).and_then(|x| x.strip_suffix(*q)));
+  match unquoted {
+    Some(x) => Ok(x.to_string()),
+    None if s.is_empty() => Err(Error::DataParse("表达式缺少操作数".to_string())),
+    None => Ok(s.to_string()),
+  }
+}
+
+impl FromStr for Expr {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+      match (quote, c) {
+        (Some(q), c) if c == q => quote = None,
+        (Some(_), _) => {}
+        (None, '"' | '\'') => quote = Some(c),
+        (None, _) => {
+          if let Some((op, o)) = Op::ALL.iter().find(|(op, _)| s[i..].starts_with(op)) {
+            return Ok(Self {
+              lhs: operand(&s[..i])?,
+              rhs: Some((*o, operand(&s[i + op.len()..])?)),
+            });
+          }
+        }
+      }
+    }
+    if quote.is_some() {
+      return Err(Error::DataParse(format!("表达式引号不匹配: {s}")));
+    }
+    Ok(Self {
+      lhs: operand(s)?,
+      rhs: None,
+    })
+  }
+}
+
+impl Expr {
+  /// 替换操作数中的变量后求值, 两侧均为数字时按数值比较, 否则按文本比较
+  pub fn eval(&self, render: impl Fn(&str) -> Result<String>) -> Result<bool> {
+    let lhs = render(&self.lhs)?;
+    let Some((op, rhs)) = &self.rhs else {
+      let v = lhs.trim().to_lowercase();
+      return Ok(!matches!(&*v, "" | "0" | "false" | "null"));
+    };
+    let rhs = render(rhs)?;
+    let ord = match (lhs.trim().parse::<f64>(), rhs.trim().parse::<f64>()) {
+      (Ok(a), Ok(b)) => a.partial_cmp(&b),
+      _ => Some(lhs.cmp(&rhs)),
+    };
+    Ok(ord.is_some_and(|ord| match op {
+      Op::Eq => ord.is_eq(),
+      Op::Ne => ord.is_ne(),
+      Op::Ge => ord.is_ge(),
+      Op::Le => ord.is_le(),
+      Op::Gt => ord.is_gt(),
+      Op::Lt => ord.is_lt(),
+    }))
+  }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// 运行控制指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+  Run,
+  /// 当前步骤完成后暂停
+  Pause,
+  /// 再执行一个步骤后暂停
+  Step,
+  /// 中止, 结束运行中的进程
+  Abort,
+}
+
+/// 运行控制句柄, 每次运行新建
+#[derive(Debug, Clone)]
+pub struct RunControl(Arc<watch::Sender<Control>>);
+impl Default for RunControl {
+  fn default() -> Self {
+    Self(Arc::new(watch::Sender::new(Control::Run)))
+  }
+}
+
+impl RunControl {
+  pub fn get(&self) -> Control {
+    *self.0.borrow()
+  }
+
+  /// 已中止后不再接受其他指令
+  pub fn set(&self, control: Control) {
+    self.0.send_if_modified(|c| {
+      let changed = *c != Control::Abort && *c != control;
+      if changed {
+        *c = control;
+      }
+      changed
+    });
+  }
+
+  /// 步骤开始前调用, 暂停时等待; 返回`false`表示已中止
+  pub async fn gate(&self) -> bool {
+    let mut rx = self.0.subscribe();
+    loop {
+      let mut go = None;
+      self.0.send_if_modified(|c| match c {
+        Control::Run => {
+          go = Some(true);
+          false
+        }
+        Control::Abort => {
+          go = Some(false);
+          false
+        }
+        Control::Step => {
+          *c = Control::Pause;
+          go = Some(true);
+          true
+        }
+        Control::Pause => false,
+      });
+      if let Some(go) = go {
+        return go;
+      }
+      if rx.changed().await.is_err() {
+        return false;
+      }
+    }
+  }
+
+  /// 等待中止
+  pub async fn aborted(&self) {
+    let mut rx = self.0.subscribe();
+    let _ = rx.wait_for(|c| *c == Control::Abort).await;
+  }
+
+  pub fn is_aborted(&self) -> bool {
+    self.get() == Control::Abort
+  }
+}
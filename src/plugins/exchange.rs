@@ -0,0 +1,262 @@
+//! 测试序列导入导出, 支持 TOML/JSON/CSV, 按`tag`比较和合并
+use std::{
+  fmt::{self, Display},
+  path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+  db::r#type::app::ExtendApp,
+  res::{Error, Result},
+};
+
+/// 导出时省略的字段, 便于版本管理
+const SKIP_KEYS: [&str; 3] = ["id", "createdAt", "updatedAt"];
+/// CSV 中按原文保存的列, 其他列为 JSON
+const TEXT_COLUMNS: [&str; 5] = ["tag", "label", "cwd", "resUrl", "capture"];
+
+/// 文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  Toml,
+  Json,
+  Csv,
+}
+impl Format {
+  /// 按扩展名判断
+  pub fn from_path(path: &Path) -> Result<Self> {
+    match path.extension().and_then(|x| x.to_str()).map(str::to_lowercase).as_deref() {
+      Some("toml") => Ok(Format::Toml),
+      Some("json") => Ok(Format::Json),
+      Some("csv") => Ok(Format::Csv),
+      _ => Err(Error::Unsupport(format!("不支持的文件格式: {}", path.display()).into())),
+    }
+  }
+}
+
+/// 导入方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mode {
+  /// 更新同名步骤并追加新步骤, 保留其他步骤
+  #[default]
+  Merge,
+  /// 以文件为准, 删除文件中没有的步骤
+  Replace,
+}
+impl Mode {
+  pub const ALL: [Mode; 2] = [Mode::Merge, Mode::Replace];
+}
+impl Display for Mode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Mode::Merge => write!(f, "合并"),
+      Mode::Replace => write!(f, "替换"),
+    }
+  }
+}
+
+/// TOML 顶层必须为表
+#[derive(Serialize, Deserialize)]
+struct SequenceFile {
+  #[serde(default)]
+  steps: Vec<Value>,
+}
+
+/// 导出的字段, 省略`SKIP_KEYS`
+fn to_object(app: &ExtendApp) -> Result<Map<String, Value>> {
+  match serde_json::to_value(app).map_err(|e| Error::DataParse(e.to_string()))? {
+    Value::Object(mut map) => {
+      for key in SKIP_KEYS {
+        map.remove(key);
+      }
+      Ok(map)
+    }
+    _ => Err(Error::DataParse(format!("[{}] 无法导出", app.tag))),
+  }
+}
+
+/// 去掉空值, TOML 不支持`null`
+fn strip_null(value: Value) -> Value {
+  match value {
+    Value::Object(map) => Value::Object(
+      map
+        .into_iter()
+        .filter(|(_, v)| !v.is_null())
+        .map(|(k, v)| (k, strip_null(v)))
+        .collect(),
+    ),
+    Value::Array(arr) => Value::Array(arr.into_iter().filter(|v| !v.is_null()).map(strip_null).collect()),
+    v => v,
+  }
+}
+
+/// 导出为文本
+pub fn export(apps: &[ExtendApp], format: Format) -> Result<String> {
+  let steps = apps.iter().map(to_object).collect::<Result<Vec<_>>>()?;
+  match format {
+    Format::Json => serde_json::to_string_pretty(&SequenceFile {
+      steps: steps.into_iter().map(Value::Object).collect(),
+    })
+    .map_err(|e| Error::DataParse(e.to_string())),
+    Format::Toml => toml::to_string_pretty(&SequenceFile {
+      steps: steps.into_iter().map(|x| strip_null(Value::Object(x))).collect(),
+    })
+    .map_err(|e| Error::DataParse(e.to_string())),
+    Format::Csv => {
+      let columns: Vec<String> = to_object(&ExtendApp::default())?.keys().cloned().collect();
+      let mut writer = csv::Writer::from_writer(vec![]);
+      writer.write_record(&columns).map_err(|e| Error::DataParse(e.to_string()))?;
+      for step in &steps {
+        let record = columns.iter().map(|k| match step.get(k) {
+          None | Some(Value::Null) => String::new(),
+          Some(Value::String(s)) if TEXT_COLUMNS.contains(&k.as_str()) => s.clone(),
+          Some(v) => v.to_string(),
+        });
+        writer.write_record(record).map_err(|e| Error::DataParse(e.to_string()))?;
+      }
+      let bytes = writer.into_inner().map_err(|e| Error::DataParse(e.to_string()))?;
+      String::from_utf8(bytes).map_err(|e| Error::DataParse(e.to_string()))
+    }
+  }
+}
+
+/// 从文本导入
+pub fn import(content: &str, format: Format) -> Result<Vec<ExtendApp>> {
+  let steps: Vec<Value> = match format {
+    Format::Json => serde_json::from_str::<SequenceFile>(content)
+      .map_err(|e| Error::DataParse(format!("JSON: {e}")))?
+      .steps,
+    Format::Toml => {
+      toml::from_str::<SequenceFile>(content)
+        .map_err(|e| Error::DataParse(format!("TOML: {e}")))?
+        .steps
+    }
+    Format::Csv => {
+      let mut reader = csv::Reader::from_reader(content.as_bytes());
+      let columns = reader.headers().map_err(|e| Error::DataParse(format!("CSV: {e}")))?.clone();
+      let mut steps = vec![];
+      for (line, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| Error::DataParse(format!("CSV: {e}")))?;
+        let mut step = Map::new();
+        for (k, v) in columns.iter().zip(record.iter()) {
+          let value = match (TEXT_COLUMNS.contains(&k), v.is_empty()) {
+            (_, true) => continue,
+            (true, false) => Value::String(v.to_string()),
+            (false, false) => serde_json::from_str(v)
+              .map_err(|e| Error::DataParse(format!("CSV 第{}行 {k}: {e}", line + 2)))?,
+          };
+          step.insert(k.to_string(), value);
+        }
+        steps.push(Value::Object(step));
+      }
+      steps
+    }
+  };
+  steps
+    .into_iter()
+    .enumerate()
+    .map(|(i, step)| {
+      serde_json::from_value::<ExtendApp>(step).map_err(|e| Error::DataParse(format!("第{}个步骤: {e}", i + 1)))
+    })
+    .collect()
+}
+
+/// 导入前后的差异
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+  pub added: Vec<String>,
+  pub removed: Vec<String>,
+  /// 步骤`tag`及变化的字段
+  pub changed: Vec<(String, Vec<String>)>,
+}
+impl Diff {
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+  }
+}
+impl Display for Diff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_empty() {
+      return writeln!(f, "无变化");
+    }
+    for tag in &self.added {
+      writeln!(f, "+ {tag}")?;
+    }
+    for tag in &self.removed {
+      writeln!(f, "- {tag}")?;
+    }
+    for (tag, fields) in &self.changed {
+      writeln!(f, "~ {tag}: {}", fields.join(", "))?;
+    }
+    Ok(())
+  }
+}
+
+/// 比较导入后的变化, `Merge`不删除步骤
+pub fn diff(current: &[ExtendApp], incoming: &[ExtendApp], mode: Mode) -> Result<Diff> {
+  let mut diff = Diff::default();
+  for app in incoming {
+    match current.iter().find(|x| x.tag == app.tag) {
+      None => diff.added.push(app.tag.clone()),
+      Some(old) => {
+        let (old, new) = (to_object(old)?, to_object(app)?);
+        let fields: Vec<String> = new
+          .iter()
+          .filter(|(k, v)| old.get(*k) != Some(v))
+          .map(|(k, _)| k.clone())
+          .collect();
+        if !fields.is_empty() {
+          diff.changed.push((app.tag.clone(), fields));
+        }
+      }
+    }
+  }
+  if mode == Mode::Replace {
+    diff.removed = current
+      .iter()
+      .filter(|x| incoming.iter().all(|y| y.tag != x.tag))
+      .map(|x| x.tag.clone())
+      .collect();
+  }
+  Ok(diff)
+}
+
+/// 按`tag`导入, 同名步骤保留原`id`, 结果按`priority`排序
+pub fn apply(current: Vec<ExtendApp>, incoming: Vec<ExtendApp>, mode: Mode) -> Vec<ExtendApp> {
+  let with_id = |app: ExtendApp, current: &[ExtendApp]| ExtendApp {
+    id: current.iter().find(|x| x.tag == app.tag).map(|x| x.id).unwrap_or_default(),
+    ..app
+  };
+  let mut apps: Vec<ExtendApp> = match mode {
+    Mode::Replace => incoming.into_iter().map(|x| with_id(x, &current)).collect(),
+    Mode::Merge => {
+      let mut apps = current;
+      for app in incoming {
+        match apps.iter_mut().find(|x| x.tag == app.tag) {
+          Some(old) => *old = ExtendApp { id: old.id, ..app },
+          None => apps.push(ExtendApp { id: 0, ..app }),
+        }
+      }
+      apps
+    }
+  };
+  apps.sort_by_key(|x| x.priority);
+  apps
+}
+
+/// 导出到文件, 格式由扩展名决定; 相对路径基于当前目录, 界面中由调用方基于程序目录
+pub async fn export_file(apps: Vec<ExtendApp>, path: impl AsRef<Path>) -> Result<()> {
+  let path = path.as_ref();
+  let content = export(&apps, Format::from_path(path)?)?;
+  tokio::fs::write(path, content).await?;
+  Ok(())
+}
+
+/// 从文件导入, 格式由扩展名决定; 相对路径同`export_file`
+pub async fn import_file(path: impl AsRef<Path>) -> Result<Vec<ExtendApp>> {
+  let path = path.as_ref();
+  let content = tokio::fs::read_to_string(path).await?;
+  import(&content, Format::from_path(path)?)
+}
@@ -0,0 +1,193 @@
+use std::{
+  path::PathBuf,
+  process::Stdio,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+
+use tokio::{
+  io::{AsyncBufReadExt as _, AsyncRead, BufReader},
+  process::Command,
+  sync::mpsc::UnboundedSender,
+  task::JoinSet,
+  time::Instant,
+};
+
+use crate::{
+  config::logger::{log, Tag},
+  data::ORIGIN,
+  db::r#type::app::ExtendApp,
+  res::{Error, PanicAny as _, Result},
+};
+
+/// 超时或中止后等待输出读取完成的时长
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 输出流
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+  Stdout,
+  Stderr,
+}
+
+/// 实时输出, 每次发送一行(不含换行符)
+pub type LineTx = UnboundedSender<(Stream, String)>;
+
+/// 单次执行输出
+#[derive(Debug, Clone, Default)]
+pub struct StepOutput {
+  pub code: Option<i32>,
+  pub stdout: String,
+  pub stderr: String,
+  pub timeout: bool,
+  pub elapsed: Duration,
+}
+impl StepOutput {
+  /// 正常退出且返回码为0
+  pub fn success(&self) -> bool {
+    !self.timeout && self.code == Some(0)
+  }
+}
+
+impl ExtendApp {
+  /// 超时时长(秒), `timeout <= 0` 不限制
+  pub fn timeout_duration(&self) -> Option<Duration> {
+    (self.timeout > 0).then(|| Duration::from_secs(self.timeout as u64))
+  }
+
+  /// 最大执行次数
+  pub fn attempts(&self) -> u32 {
+    if self.is_repeat {
+      self.count.max(1) as u32
+    } else {
+      1
+    }
+  }
+}
+
+/// 结束进程及其所有子进程
+pub fn kill_tree(pid: u32) {
+  #[cfg(windows)]
+  let res = {
+    use std::os::windows::process::CommandExt as _;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    std::process::Command::new("taskkill")
+      .args(["/T", "/F", "/PID", &pid.to_string()])
+      .creation_flags(CREATE_NO_WINDOW)
+      .output()
+  };
+  // 子进程以自身PID为进程组, 结束整个进程组; `--`之后的负数才会被当作进程组
+  #[cfg(not(windows))]
+  let res = std::process::Command::new("kill")
+    .args(["-KILL", "--", &format!("-{pid}")])
+    .output();
+  match res {
+    Ok(output) if !output.status.success() => {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      log::warn(format!("结束进程树[{pid}]失败: {} {}", output.status, stderr.trim()), Tag::AutoTest);
+    }
+    Ok(_) => {}
+    Err(e) => log::warn(format!("结束进程树[{pid}]失败: {e}"), Tag::AutoTest),
+  }
+}
+
+/// 进程树守卫, 未正常退出时(超时、中止)结束整个进程树
+struct TreeGuard(Option<u32>);
+impl TreeGuard {
+  fn disarm(&mut self) {
+    self.0 = None;
+  }
+}
+impl Drop for TreeGuard {
+  fn drop(&mut self) {
+    if let Some(pid) = self.0.take() {
+      kill_tree(pid);
+    }
+  }
+}
+
+/// 持续读取输出到缓冲区并逐行转发到`lines`, 进程被结束时保留已读取的部分
+fn read_to(
+  readers: &mut JoinSet<()>,
+  reader: Option<impl AsyncRead + Unpin + Send + 'static>,
+  stream: Stream,
+  buf: Arc<Mutex<String>>,
+  lines: Option<LineTx>,
+) {
+  let Some(reader) = reader else { return };
+  readers.spawn(async move {
+    let mut reader = BufReader::new(reader);
+    let mut line = vec![];
+    while let Ok(n) = reader.read_until(b'\n', &mut line).await {
+      if n == 0 {
+        break;
+      }
+      let text = String::from_utf8_lossy(&line);
+      buf.lock().panic("read output").push_str(&text);
+      if let Some(lines) = &lines {
+        let _ = lines.send((stream, text.trim_end_matches(['\r', '\n']).to_string()));
+      }
+      line.clear();
+    }
+  });
+}
+
+/// 步骤的工作目录, 相对路径基于程序目录`ORIGIN`, 未设置`cwd`时即`ORIGIN`
+///
+/// 执行、文件检查及运行前检查都按此解析
+pub fn work_dir(app: &ExtendApp) -> PathBuf {
+  match app.cwd.as_deref().filter(|x| !x.is_empty()) {
+    Some(cwd) => ORIGIN.join(cwd),
+    None => ORIGIN.to_path_buf(),
+  }
+}
+
+/// 在`cwd`中启动`cmd`并等待退出, 超时则结束整个进程树并保留已输出的内容
+///
+/// `lines`不为空时实时转发每一行输出
+pub async fn execute(app: &ExtendApp, lines: Option<LineTx>) -> Result<StepOutput> {
+  let (program, args) = app
+    .cmd
+    .split_first()
+    .ok_or(Error::DataParse(format!("[{}] 命令为空", app.tag)))?;
+  let mut cmd = Command::new(program);
+  cmd
+    .args(args)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true);
+  #[cfg(unix)]
+  cmd.process_group(0);
+  cmd.current_dir(work_dir(app));
+  let start = Instant::now();
+  let mut child = cmd.spawn()?;
+  let mut guard = TreeGuard(child.id());
+  let (stdout, stderr) = (Arc::new(Mutex::new(String::new())), Arc::new(Mutex::new(String::new())));
+  let mut readers = JoinSet::new();
+  read_to(&mut readers, child.stdout.take(), Stream::Stdout, stdout.clone(), lines.clone());
+  read_to(&mut readers, child.stderr.take(), Stream::Stderr, stderr.clone(), lines);
+  let status = match app.timeout_duration() {
+    Some(duration) => tokio::time::timeout(duration, child.wait()).await.ok().transpose()?,
+    None => Some(child.wait().await?),
+  };
+  match status {
+    Some(_) => guard.disarm(),
+    None => {
+      log::warn(format!("[{}] 执行超时, 结束进程树", app.tag), Tag::AutoTest);
+      drop(guard);
+      let _ = child.kill().await;
+    }
+  }
+  // 后台子进程可能仍持有输出管道, 最多等待 DRAIN_TIMEOUT, 之后随`readers`一起丢弃
+  let _ = tokio::time::timeout(DRAIN_TIMEOUT, async { while readers.join_next().await.is_some() {} }).await;
+  drop(readers);
+  let take = |buf: &Arc<Mutex<String>>| std::mem::take(&mut *buf.lock().panic("take output"));
+  Ok(StepOutput {
+    code: status.and_then(|x| x.code()),
+    stdout: take(&stdout),
+    stderr: take(&stderr),
+    timeout: status.is_none(),
+    elapsed: start.elapsed(),
+  })
+}
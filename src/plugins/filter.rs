@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use e_utils::regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::res::{Error, Result};
+
+/// 结果提取规则, 对应`ExtendApp.filter`中的一项
+///
+/// | 写法 | 说明 |
+/// | -- | -- |
+/// `regex:<pattern>` | 正则, 优先取命名分组`value`, 其次所有命名分组, 再次第1个分组, 最后整个匹配 |
+/// `json:<path>` | JSON 选择器, 如 `$.data.list[0].mac` |
+/// `line:<n>` | 取第`n`行, 从1开始, 负数从末尾计, 如`line:1`为首行、`line:-1`为末行 |
+/// `line:<n>:<col>` | 取第`n`行按空白分割后的第`col`列, 计数同行 |
+/// `<pattern>` | 无前缀按正则处理 |
+#[derive(Debug, Clone)]
+pub enum Filter {
+  Regex(Regex),
+  Json(Vec<JsonKey>),
+  Line { line: isize, column: Option<isize> },
+}
+
+/// JSON 选择器路径
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonKey {
+  Key(String),
+  Index(usize),
+}
+
+impl FromStr for Filter {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let (kind, rule) = s.split_once(':').unwrap_or(("regex", s));
+    match kind {
+      "json" => Ok(Self::Json(parse_json_path(rule)?)),
+      "line" => {
+        let parse = |x: &str| match x.trim().parse::<isize>() {
+          Ok(0) => Err(Error::DataParse(format!("行列选择 {s}: 从1开始"))),
+          Ok(v) => Ok(v),
+          Err(e) => Err(Error::DataParse(format!("行列选择 {s}: {e}"))),
+        };
+        let mut it = rule.splitn(2, ':');
+        let line = parse(it.next().unwrap_or_default())?;
+        let column = it.next().map(parse).transpose()?;
+        Ok(Self::Line { line, column })
+      }
+      "regex" => Self::regex(rule),
+      _ => Self::regex(s),
+    }
+  }
+}
+
+impl Filter {
+  fn regex(rule: &str) -> Result<Self> {
+    Regex::new(rule)
+      .map(Self::Regex)
+      .map_err(|e| Error::DataParse(format!("正则 {rule}: {e}")))
+  }
+
+  /// 提取
+  pub fn apply(&self, input: &str) -> Result<Value> {
+    match self {
+      Filter::Regex(re) => {
+        let caps = re
+          .captures(input)
+          .ok_or(Error::NotFound(format!("正则未匹配: {}", re.as_str()).into()))?;
+        let names: Vec<&str> = re.capture_names().flatten().collect();
+        if let Some(v) = caps.name("value") {
+          return Ok(Value::String(v.as_str().to_string()));
+        }
+        if !names.is_empty() {
+          let map: Map<String, Value> = names
+            .into_iter()
+            .filter_map(|k| caps.name(k).map(|v| (k.to_string(), Value::String(v.as_str().to_string()))))
+            .collect();
+          return Ok(Value::Object(map));
+        }
+        let m = caps.get(1).or(caps.get(0)).map(|x| x.as_str()).unwrap_or_default();
+        Ok(Value::String(m.to_string()))
+      }
+      Filter::Json(path) => {
+        let mut value: Value = serde_json::from_str(input.trim())
+          .map_err(|e| Error::DataParse(format!("输出不是JSON: {e}")))?;
+        for key in path {
+          value = match key {
+            JsonKey::Key(k) => value.get_mut(k).map(Value::take),
+            JsonKey::Index(i) => value.get_mut(i).map(Value::take),
+          }
+          .ok_or(Error::NotFound(format!("JSON路径不存在: {key:?}").into()))?;
+        }
+        Ok(value)
+      }
+      Filter::Line { line, column } => {
+        let lines: Vec<&str> = input.lines().collect();
+        let line = pick(&lines, *line).ok_or(Error::NotFound(format!("行不存在: {line}").into()))?;
+        match column {
+          Some(column) => {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            pick(&columns, *column)
+              .map(|x| Value::String(x.to_string()))
+              .ok_or(Error::NotFound(format!("列不存在: {column}").into()))
+          }
+          None => Ok(Value::String(line.trim().to_string())),
+        }
+      }
+    }
+  }
+}
+
+/// 按从1开始的序号取值, 负数从末尾计
+fn pick<'a>(list: &[&'a str], index: isize) -> Option<&'a str> {
+  let index = match index {
+    0 => return None,
+    i if i < 0 => list.len().checked_sub(i.unsigned_abs())?,
+    i => i as usize - 1,
+  };
+  list.get(index).copied()
+}
+
+/// 解析 `$.a.b[0]['c d']`
+fn parse_json_path(path: &str) -> Result<Vec<JsonKey>> {
+  let err = || Error::DataParse(format!("JSON路径: {path}"));
+  let path = path.trim();
+  let mut rest = path.strip_prefix('$').unwrap_or(path);
+  let mut keys = vec![];
+  while !rest.is_empty() {
+    if let Some(r) = rest.strip_prefix('[') {
+      let end = r.find(']').ok_or_else(err)?;
+      let inner = r[..end].trim();
+      keys.push(match inner.parse::<usize>() {
+        Ok(i) => JsonKey::Index(i),
+        Err(_) => JsonKey::Key(inner.trim_matches(|c| c == '\'' || c == '"').to_string()),
+      });
+      rest = &r[end + 1..];
+    } else {
+      let r = rest.strip_prefix('.').unwrap_or(rest);
+      let end = r.find(|c| c == '.' || c == '[').unwrap_or(r.len());
+      if end == 0 {
+        return Err(err());
+      }
+      keys.push(JsonKey::Key(r[..end].to_string()));
+      rest = &r[end..];
+    }
+  }
+  Ok(keys)
+}
+
+/// 依次应用提取规则, 前一项的结果作为后一项的输入; 无规则时返回去除首尾空白的输出
+pub fn extract(filters: &[String], stdout: &str) -> Result<Value> {
+  let mut value = Value::String(stdout.trim().to_string());
+  for filter in filters.iter().filter(|x| !x.trim().is_empty()) {
+    let input = match &value {
+      Value::String(s) => s.clone(),
+      v => v.to_string(),
+    };
+    value = filter.parse::<Filter>()?.apply(&input)?;
+  }
+  Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 正数从首行计、负数从末行计, 均从1开始
+  #[test]
+  fn line_index() {
+    let out = "a1 a2\nb1 b2\nc1 c2";
+    let line = |rule: &str| rule.parse::<Filter>().and_then(|x| x.apply(out)).ok();
+    assert_eq!(line("line:1"), Some(Value::from("a1 a2")));
+    assert_eq!(line("line:-1"), Some(Value::from("c1 c2")));
+    assert_eq!(line("line:3"), line("line:-1"));
+    assert_eq!(line("line:2:1"), Some(Value::from("b1")));
+    assert_eq!(line("line:2:-1"), Some(Value::from("b2")));
+    assert_eq!(line("line:4"), None);
+    assert_eq!(line("line:-4"), None);
+    assert!("line:0".parse::<Filter>().is_err());
+  }
+}
@@ -0,0 +1,47 @@
+use super::{
+  step::{ShellStep, StepRunner as _},
+  template::Template,
+};
+use crate::{
+  config::logger::{log, Tag},
+  db::r#type::app::ExtendApp,
+  res::{Error, Result},
+};
+
+/// 脚本超时(秒)
+const HOOK_TIMEOUT: i32 = 60;
+
+/// 工站前置/后置脚本
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hooks {
+  pub pre: String,
+  pub post: String,
+}
+
+/// 替换变量后执行脚本, 为空时不执行; 非0退出或超时返回错误
+pub async fn run(name: &str, script: &str, template: &Template) -> Result<()> {
+  if script.trim().is_empty() {
+    return Ok(());
+  }
+  let script = template.render(script)?;
+  let app = ExtendApp {
+    tag: name.to_string(),
+    timeout: HOOK_TIMEOUT,
+    ..Default::default()
+  };
+  log::info(format!("[{name}] {script}"), Tag::AutoTest);
+  let output = ShellStep {
+    script,
+    ..Default::default()
+  }
+  .run(&app, None)
+  .await?;
+  if output.success() {
+    return Ok(());
+  }
+  let reason = match output.timeout {
+    true => format!("超时 {HOOK_TIMEOUT}s"),
+    false => format!("退出码 {:?}: {}", output.code, output.stderr.trim()),
+  };
+  Err(Error::Io(std::io::Error::other(format!("[{name}] {reason}"))))
+}
@@ -0,0 +1,288 @@
+use std::fmt::{self, Display};
+
+use e_utils::regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 步骤限值, 存储于`externApp.limits`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Limit {
+  pub kind: LimitKind,
+  /// 单位, 如 `V`、`mA`
+  pub unit: Option<String>,
+  /// 数值显示的小数位数
+  pub precision: Option<usize>,
+}
+
+/// 限值类型
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LimitKind {
+  /// 不判定
+  #[default]
+  None,
+  /// 完全一致, 数字按数值比较
+  Exact { value: Value },
+  /// 上下限, 缺省的一侧不限制
+  #[serde(rename_all = "camelCase")]
+  Range {
+    low: Option<f64>,
+    high: Option<f64>,
+    #[serde(default = "default_true")]
+    low_inclusive: bool,
+    #[serde(default = "default_true")]
+    high_inclusive: bool,
+  },
+  /// 标称值±公差, `percent`时公差为百分比
+  Tolerance {
+    nominal: f64,
+    tolerance: f64,
+    #[serde(default)]
+    percent: bool,
+  },
+  /// 正则匹配
+  Regex { pattern: String },
+  /// 属于集合之一
+  OneOf { values: Vec<Value> },
+  /// 布尔值
+  Bool { value: bool },
+}
+fn default_true() -> bool {
+  true
+}
+
+/// 违反的限值
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+  /// 低于下限
+  Low { actual: f64, low: f64, inclusive: bool },
+  /// 高于上限
+  High { actual: f64, high: f64, inclusive: bool },
+  NotEqual,
+  NotMatch,
+  NotInSet,
+  /// 结果不是数字
+  NotNumber,
+  /// 结果不是布尔值
+  NotBool,
+  /// 限值本身无效
+  Invalid(String),
+}
+impl Display for Violation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Violation::Low { actual, low, inclusive } => {
+        write!(f, "低于下限: {actual} {} {low}", if *inclusive { "<" } else { "<=" })
+      }
+      Violation::High { actual, high, inclusive } => {
+        write!(f, "高于上限: {actual} {} {high}", if *inclusive { ">" } else { ">=" })
+      }
+      Violation::NotEqual => write!(f, "与标准值不一致"),
+      Violation::NotMatch => write!(f, "正则不匹配"),
+      Violation::NotInSet => write!(f, "不在可选值中"),
+      Violation::NotNumber => write!(f, "结果不是数字"),
+      Violation::NotBool => write!(f, "结果不是布尔值"),
+      Violation::Invalid(e) => write!(f, "限值无效: {e}"),
+    }
+  }
+}
+
+/// 结果值转数字
+pub fn as_f64(v: &Value) -> Option<f64> {
+  match v {
+    Value::Number(n) => n.as_f64(),
+    Value::String(s) => s.trim().parse().ok(),
+    Value::Bool(b) => Some(*b as i32 as f64),
+    _ => None,
+  }
+}
+
+/// 结果值转文本
+pub fn as_text(v: &Value) -> String {
+  match v {
+    Value::String(s) => s.trim().to_string(),
+    v => v.to_string(),
+  }
+}
+
+/// 比较标准值与结果值, 数字按数值比较, 其余按文本比较
+pub fn compare(expect: &Value, actual: &Value) -> bool {
+  match (as_f64(expect), as_f64(actual)) {
+    (Some(a), Some(b)) => a == b,
+    _ => as_text(expect) == as_text(actual),
+  }
+}
+
+impl Limit {
+  /// 是否需要判定
+  pub fn is_none(&self) -> bool {
+    self.kind == LimitKind::None
+  }
+
+  /// 数值上下限, 公差换算为上下限; 非数值限值返回`(None, None)`
+  pub fn bounds(&self) -> (Option<f64>, Option<f64>) {
+    match &self.kind {
+      LimitKind::Range { low, high, .. } => (*low, *high),
+      LimitKind::Tolerance {
+        nominal,
+        tolerance,
+        percent,
+      } => {
+        let delta = if *percent {
+          nominal.abs() * tolerance / 100.0
+        } else {
+          *tolerance
+        };
+        (Some(nominal - delta), Some(nominal + delta))
+      }
+      _ => (None, None),
+    }
+  }
+
+  /// 校验限值本身是否有效
+  pub fn validate(&self) -> Result<(), Violation> {
+    match &self.kind {
+      LimitKind::Range { low: Some(low), high: Some(high), .. } if low > high => {
+        Err(Violation::Invalid(format!("下限{low}大于上限{high}")))
+      }
+      LimitKind::Tolerance { tolerance, .. } if *tolerance < 0.0 => {
+        Err(Violation::Invalid(format!("公差{tolerance}小于0")))
+      }
+      LimitKind::Regex { pattern } => Regex::new(pattern)
+        .map(|_| ())
+        .map_err(|e| Violation::Invalid(e.to_string())),
+      _ => Ok(()),
+    }
+  }
+
+  /// 判定结果值
+  pub fn check(&self, actual: &Value) -> Result<(), Violation> {
+    match &self.kind {
+      LimitKind::None => Ok(()),
+      LimitKind::Exact { value } => compare(value, actual).then_some(()).ok_or(Violation::NotEqual),
+      LimitKind::Range {
+        low,
+        high,
+        low_inclusive,
+        high_inclusive,
+      } => {
+        let actual = as_f64(actual).ok_or(Violation::NotNumber)?;
+        check_range(actual, *low, *high, *low_inclusive, *high_inclusive)
+      }
+      LimitKind::Tolerance { .. } => {
+        let actual = as_f64(actual).ok_or(Violation::NotNumber)?;
+        let (low, high) = self.bounds();
+        check_range(actual, low, high, true, true)
+      }
+      LimitKind::Regex { pattern } => {
+        let re = Regex::new(pattern).map_err(|e| Violation::Invalid(e.to_string()))?;
+        re.is_match(&as_text(actual)).then_some(()).ok_or(Violation::NotMatch)
+      }
+      LimitKind::OneOf { values } => values
+        .iter()
+        .any(|v| compare(v, actual))
+        .then_some(())
+        .ok_or(Violation::NotInSet),
+      LimitKind::Bool { value } => {
+        let actual = match actual {
+          Value::Bool(b) => *b,
+          v => match as_text(v).to_lowercase().as_str() {
+            "true" | "1" | "yes" | "ok" | "pass" => true,
+            "false" | "0" | "no" | "ng" | "fail" => false,
+            _ => return Err(Violation::NotBool),
+          },
+        };
+        (actual == *value).then_some(()).ok_or(Violation::NotEqual)
+      }
+    }
+  }
+
+  /// 按单位和小数位数显示结果值
+  pub fn format_value(&self, v: &Value) -> String {
+    let text = match (self.precision, as_f64(v)) {
+      (Some(p), Some(n)) if !matches!(v, Value::Bool(_)) => format!("{n:.p$}"),
+      _ => match v {
+        Value::Null => return String::new(),
+        v => as_text(v),
+      },
+    };
+    match &self.unit {
+      Some(unit) => format!("{text} {unit}"),
+      None => text,
+    }
+  }
+
+  fn num(&self, n: f64) -> String {
+    match self.precision {
+      Some(p) => format!("{n:.p$}"),
+      None => n.to_string(),
+    }
+  }
+}
+
+fn check_range(
+  actual: f64,
+  low: Option<f64>,
+  high: Option<f64>,
+  low_inclusive: bool,
+  high_inclusive: bool,
+) -> Result<(), Violation> {
+  if let Some(low) = low {
+    if actual < low || (!low_inclusive && actual == low) {
+      return Err(Violation::Low {
+        actual,
+        low,
+        inclusive: low_inclusive,
+      });
+    }
+  }
+  if let Some(high) = high {
+    if actual > high || (!high_inclusive && actual == high) {
+      return Err(Violation::High {
+        actual,
+        high,
+        inclusive: high_inclusive,
+      });
+    }
+  }
+  Ok(())
+}
+
+impl Display for Limit {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = match &self.kind {
+      LimitKind::None => return Ok(()),
+      LimitKind::Exact { value } => format!("= {}", as_text(value)),
+      LimitKind::Range {
+        low,
+        high,
+        low_inclusive,
+        high_inclusive,
+      } => format!(
+        "{}{}, {}{}",
+        if *low_inclusive { "[" } else { "(" },
+        low.map(|x| self.num(x)).unwrap_or("-∞".to_string()),
+        high.map(|x| self.num(x)).unwrap_or("+∞".to_string()),
+        if *high_inclusive { "]" } else { ")" },
+      ),
+      LimitKind::Tolerance {
+        nominal,
+        tolerance,
+        percent,
+      } => format!(
+        "{}±{}{}",
+        self.num(*nominal),
+        self.num(*tolerance),
+        if *percent { "%" } else { "" }
+      ),
+      LimitKind::Regex { pattern } => format!("/{pattern}/"),
+      LimitKind::OneOf { values } => format!("{{{}}}", values.iter().map(as_text).collect::<Vec<_>>().join(", ")),
+      LimitKind::Bool { value } => value.to_string(),
+    };
+    match &self.unit {
+      Some(unit) => write!(f, "{s} {unit}"),
+      None => write!(f, "{s}"),
+    }
+  }
+}
@@ -1 +1,18 @@
-
+//! 扩展应用(测试步骤)执行引擎
+pub mod condition;
+pub mod control;
+pub mod exchange;
+pub mod executor;
+pub mod filter;
+pub mod hook;
+pub mod limit;
+pub mod report;
+pub mod retry;
+pub mod schedule;
+pub mod sequence;
+pub mod spc;
+pub mod stats;
+pub mod step;
+pub mod template;
+pub mod upload;
+pub mod validate;
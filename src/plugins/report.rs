@@ -0,0 +1,359 @@
+//! 测试报告, 每次测试结束后生成 HTML、JUnit XML、CSV
+use std::{collections::HashMap, fmt::Write as _, path::PathBuf, time::Duration};
+
+use super::{
+  limit::{as_text, Limit},
+  sequence::StepResult,
+  template::Template,
+};
+use crate::{
+  config::Config,
+  data::ORIGIN,
+  db::r#type::{
+    app::ExtendApp,
+    run::{TestRun, TestStepResult},
+  },
+  res::{Error, Result},
+  ui::main_app::autotest::DataState,
+};
+
+/// 文件名中不允许的字符
+const INVALID_CHARS: [char; 9] = ['\\', '/', ':', '*', '?', '"', '<', '>', '|'];
+/// CSV 列
+const CSV_COLUMNS: [&str; 16] = [
+  "runId",
+  "sn",
+  "station",
+  "result",
+  "startedAt",
+  "tag",
+  "label",
+  "state",
+  "value",
+  "unit",
+  "low",
+  "high",
+  "limits",
+  "violation",
+  "attempts",
+  "durationMs",
+];
+
+/// 报告中的一个步骤
+#[derive(Debug, Clone)]
+pub struct StepRow {
+  pub tag: String,
+  pub label: String,
+  pub state: DataState,
+  /// 按单位和小数位数显示的结果值
+  pub value: String,
+  pub limits: Limit,
+  pub violation: Option<String>,
+  pub attempts: usize,
+  /// 全部执行次数的耗时
+  pub duration_ms: i64,
+  /// 最后一次执行的输出
+  pub stdout: String,
+  pub stderr: String,
+}
+impl StepRow {
+  pub fn new(app: &ExtendApp, res: &StepResult) -> Self {
+    let output = res.output().cloned().unwrap_or_default();
+    let duration: Duration = res.attempts.iter().filter_map(|x| x.output.as_ref()).map(|x| x.elapsed).sum();
+    Self {
+      tag: app.tag.clone(),
+      label: app.label.clone(),
+      state: res.state,
+      value: app.limits.format_value(&res.res_value),
+      limits: app.limits.clone(),
+      violation: res.violation.as_ref().map(|x| x.to_string()),
+      attempts: res.attempts.len(),
+      duration_ms: duration.as_millis() as i64,
+      stdout: output.stdout,
+      stderr: output.stderr,
+    }
+  }
+
+  /// 由已保存的步骤记录生成, 同一步骤多次执行时取最后一次的结果
+  pub fn from_records(records: &[TestStepResult]) -> Vec<Self> {
+    let mut rows: Vec<Self> = vec![];
+    for record in records {
+      let limits: Limit = serde_json::from_value(record.limits.clone()).unwrap_or_default();
+      let row = Self {
+        tag: record.tag.clone(),
+        label: record.label.clone(),
+        state: DataState::from(record.state.as_str()),
+        value: limits.format_value(&record.res_value),
+        limits,
+        violation: record.violation.clone(),
+        attempts: record.attempt.max(0) as usize,
+        duration_ms: record.duration_ms,
+        stdout: record.stdout.clone(),
+        stderr: record.stderr.clone(),
+      };
+      match rows.iter_mut().find(|x| x.tag == row.tag) {
+        Some(old) => {
+          let duration_ms = old.duration_ms + row.duration_ms;
+          *old = Self { duration_ms, ..row };
+        }
+        None => rows.push(row),
+      }
+    }
+    rows
+  }
+}
+
+/// 转义 HTML/XML 文本及属性
+fn escape(s: &str) -> String {
+  let mut res = String::with_capacity(s.len());
+  for c in s.chars() {
+    match c {
+      '&' => res.push_str("&amp;"),
+      '<' => res.push_str("&lt;"),
+      '>' => res.push_str("&gt;"),
+      '"' => res.push_str("&quot;"),
+      '\'' => res.push_str("&apos;"),
+      // XML 1.0 不允许的控制字符
+      c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+      c => res.push(c),
+    }
+  }
+  res
+}
+
+/// 总耗时(秒)
+fn elapsed(run: &TestRun) -> f64 {
+  run
+    .ended_at
+    .map(|x| (x - run.started_at).num_milliseconds() as f64 / 1000.0)
+    .unwrap_or_default()
+}
+
+/// 自包含的 HTML 页面, 含步骤表及限值
+pub fn html(run: &TestRun, steps: &[StepRow]) -> String {
+  let color = |state: DataState| match state {
+    DataState::Success => "pass",
+    DataState::Skipped | DataState::Ready | DataState::Running => "skip",
+    DataState::Fail | DataState::Timeout | DataState::Aborted => "fail",
+  };
+  let mut rows = String::new();
+  for (i, step) in steps.iter().enumerate() {
+    let cells = [
+      (i + 1).to_string(),
+      escape(&step.tag),
+      escape(&step.label),
+      format!("<span class=\"{}\">{}</span>", color(step.state), step.state.as_str()),
+      escape(&step.value),
+      escape(&step.limits.to_string()),
+      escape(step.violation.as_deref().unwrap_or_default()),
+      step.attempts.to_string(),
+      step.duration_ms.to_string(),
+    ];
+    let _ = writeln!(rows, "<tr><td>{}</td></tr>", cells.join("</td><td>"));
+  }
+  let mut vars = String::new();
+  if let Some(map) = run.vars.as_object() {
+    for (k, v) in map {
+      let _ = writeln!(vars, "<tr><th>{}</th><td>{}</td></tr>", escape(k), escape(&as_text(v)));
+    }
+  }
+  let state = DataState::from(run.result.as_str());
+  format!(
+    r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<title>{sn} {station} {result}</title>
+<style>
+body {{ font-family: sans-serif; margin: 24px; color: #222; }}
+table {{ border-collapse: collapse; margin-bottom: 24px; }}
+th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}
+th {{ background: #f3f3f3; }}
+.pass {{ color: #1a7f37; font-weight: bold; }}
+.fail {{ color: #cf222e; font-weight: bold; }}
+.skip {{ color: #9a6700; }}
+</style>
+</head>
+<body>
+<h1>测试报告 <span class="{class}">{result}</span></h1>
+<table>
+<tr><th>SN</th><td>{sn}</td></tr>
+<tr><th>工站</th><td>{station}</td></tr>
+<tr><th>记录ID</th><td>{id}</td></tr>
+<tr><th>开始时间</th><td>{started}</td></tr>
+<tr><th>结束时间</th><td>{ended}</td></tr>
+<tr><th>耗时(秒)</th><td>{elapsed:.3}</td></tr>
+</table>
+<h2>步骤</h2>
+<table>
+<tr><th>#</th><th>标签</th><th>名称</th><th>结果</th><th>结果值</th><th>限值</th>
+<th>超限</th><th>执行次数</th><th>耗时(ms)</th></tr>
+{rows}</table>
+<h2>运行变量</h2>
+<table>
+{vars}</table>
+</body>
+</html>
+"#,
+    sn = escape(&run.sn),
+    station = escape(&run.station),
+    result = escape(&run.result),
+    class = color(state),
+    id = run.id,
+    started = run.started_at.to_rfc3339(),
+    ended = run.ended_at.map(|x| x.to_rfc3339()).unwrap_or_default(),
+    elapsed = elapsed(run),
+  )
+}
+
+/// JUnit XML, 工站为测试套件, 步骤为测试用例
+pub fn junit(run: &TestRun, steps: &[StepRow]) -> String {
+  let count = |f: fn(DataState) -> bool| steps.iter().filter(|x| f(x.state)).count();
+  let failures = count(|x| x == DataState::Fail);
+  let errors = count(|x| x == DataState::Timeout);
+  let skipped = count(|x| matches!(x, DataState::Skipped | DataState::Aborted));
+  let (suite, sn) = (escape(&run.station), escape(&run.sn));
+  let counts = format!(
+    "tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{:.3}\"",
+    steps.len(),
+    elapsed(run),
+  );
+  let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+  let _ = writeln!(xml, "<testsuites name=\"{suite}\" {counts}>");
+  let _ = writeln!(
+    xml,
+    "  <testsuite name=\"{suite}\" id=\"{}\" {counts} timestamp=\"{}\">",
+    run.id,
+    run.started_at.format("%Y-%m-%dT%H:%M:%S"),
+  );
+  xml.push_str("    <properties>\n");
+  let _ = writeln!(xml, "      <property name=\"sn\" value=\"{sn}\"/>");
+  let _ = writeln!(xml, "      <property name=\"result\" value=\"{}\"/>", escape(&run.result));
+  if let Some(map) = run.vars.as_object() {
+    for (k, v) in map {
+      let _ = writeln!(xml, "      <property name=\"{}\" value=\"{}\"/>", escape(k), escape(&as_text(v)));
+    }
+  }
+  xml.push_str("    </properties>\n");
+  for step in steps {
+    let _ = write!(
+      xml,
+      "    <testcase name=\"{}\" classname=\"{suite}.{sn}\" time=\"{:.3}\">",
+      escape(&step.tag),
+      step.duration_ms as f64 / 1000.0,
+    );
+    let detail = escape(&format!("结果值: {} 限值: {}", step.value, step.limits));
+    let message = escape(step.violation.as_deref().unwrap_or(step.value.as_str()));
+    match step.state {
+      DataState::Success => {}
+      DataState::Fail => {
+        let _ = write!(xml, "\n      <failure message=\"{message}\" type=\"Fail\">{detail}</failure>");
+      }
+      DataState::Timeout => {
+        let _ = write!(xml, "\n      <error message=\"{message}\" type=\"Timeout\">{detail}</error>");
+      }
+      state => {
+        let _ = write!(xml, "\n      <skipped message=\"{}\"/>", state.as_str());
+      }
+    }
+    if !step.stdout.is_empty() {
+      let _ = write!(xml, "\n      <system-out>{}</system-out>", escape(&step.stdout));
+    }
+    if !step.stderr.is_empty() {
+      let _ = write!(xml, "\n      <system-err>{}</system-err>", escape(&step.stderr));
+    }
+    if step.state != DataState::Success || !step.stdout.is_empty() || !step.stderr.is_empty() {
+      xml.push_str("\n    ");
+    }
+    xml.push_str("</testcase>\n");
+  }
+  xml.push_str("  </testsuite>\n</testsuites>\n");
+  xml
+}
+
+/// 每个步骤一行
+pub fn csv(run: &TestRun, steps: &[StepRow]) -> Result<String> {
+  let num = |x: Option<f64>| x.map(|x| x.to_string()).unwrap_or_default();
+  let mut writer = csv::Writer::from_writer(vec![]);
+  writer.write_record(CSV_COLUMNS).map_err(|e| Error::DataParse(e.to_string()))?;
+  for step in steps {
+    let (low, high) = step.limits.bounds();
+    writer
+      .write_record([
+        run.id.to_string(),
+        run.sn.clone(),
+        run.station.clone(),
+        run.result.clone(),
+        run.started_at.to_rfc3339(),
+        step.tag.clone(),
+        step.label.clone(),
+        step.state.as_str().to_string(),
+        step.value.clone(),
+        step.limits.unit.clone().unwrap_or_default(),
+        num(low),
+        num(high),
+        step.limits.to_string(),
+        step.violation.clone().unwrap_or_default(),
+        step.attempts.to_string(),
+        step.duration_ms.to_string(),
+      ])
+      .map_err(|e| Error::DataParse(e.to_string()))?;
+  }
+  let bytes = writer.into_inner().map_err(|e| Error::DataParse(e.to_string()))?;
+  String::from_utf8(bytes).map_err(|e| Error::DataParse(e.to_string()))
+}
+
+/// 替换文件名中不允许的字符
+fn sanitize(fname: &str) -> String {
+  fname
+    .chars()
+    .map(|c| match INVALID_CHARS.contains(&c) || c.is_control() {
+      true => '_',
+      false => c,
+    })
+    .collect()
+}
+
+/// 按配置写入报告, 返回生成的文件
+///
+/// 目录和文件名由`template`替换变量, 另提供`#timestamp#`(开始时间, UTC`YYYYmmddHHMMSS`);
+/// 相对目录基于程序目录
+pub async fn write(template: &Template, run: &TestRun, steps: &[StepRow]) -> Result<Vec<PathBuf>> {
+  let ref cfg = template.config().report;
+  template.set("timestamp", run.started_at.format("%Y%m%d%H%M%S").to_string());
+  let folder = ORIGIN.join(template.render(&cfg.folder)?);
+  let fname = sanitize(&template.render(&cfg.fname)?);
+  if fname.is_empty() {
+    return Err(Error::DataParse("报告文件名为空".to_string()));
+  }
+  tokio::fs::create_dir_all(&folder).await?;
+  let mut files = vec![];
+  if cfg.html {
+    files.push((folder.join(format!("{fname}.html")), html(run, steps)));
+  }
+  if cfg.junit {
+    files.push((folder.join(format!("{fname}.xml")), junit(run, steps)));
+  }
+  if cfg.csv {
+    files.push((folder.join(format!("{fname}.csv")), csv(run, steps)?));
+  }
+  for (path, content) in &files {
+    tokio::fs::write(path, content).await?;
+  }
+  Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// 按当前配置重新生成已保存测试记录的报告, 变量取自测试记录
+pub async fn export_run(cfg: Config, run: TestRun, records: Vec<TestStepResult>) -> Result<Vec<PathBuf>> {
+  let mut vars: HashMap<String, String> = run
+    .vars
+    .as_object()
+    .map(|map| map.iter().map(|(k, v)| (k.clone(), as_text(v))).collect())
+    .unwrap_or_default();
+  vars.insert("sn".to_string(), run.sn.clone());
+  vars.insert("station".to_string(), run.station.clone());
+  vars.insert("run_id".to_string(), run.id.to_string());
+  vars.insert("result".to_string(), run.result.clone());
+  let template = Template::new(cfg, vars);
+  write(&template, &run, &StepRow::from_records(&records)).await
+}
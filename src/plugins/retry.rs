@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// 重试策略, 存储于`externApp.retry`, 仅在`is_repeat`时生效, 次数由`count`决定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Retry {
+  pub mode: RetryMode,
+  /// `Repeat`模式下的结果汇总方式
+  pub aggregate: Aggregate,
+  pub backoff: Backoff,
+}
+
+/// 重试模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RetryMode {
+  /// 失败重试, 通过即停止
+  #[default]
+  UntilPass,
+  /// 固定执行`count`次后汇总
+  Repeat,
+}
+
+/// 多次执行的结果汇总
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Aggregate {
+  /// 全部通过
+  #[default]
+  AllPass,
+  /// 任意一次通过
+  AnyPass,
+  /// 取结果值中位数再判定限值
+  Median,
+}
+
+/// 两次执行之间的等待
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Backoff {
+  /// 固定间隔
+  #[serde(rename_all = "camelCase")]
+  Fixed { delay_ms: u64 },
+  /// 指数退避, 第n次重试等待 `delay_ms * factor^(n-1)`, 不超过`max_ms`
+  #[serde(rename_all = "camelCase")]
+  Exponential { delay_ms: u64, factor: f64, max_ms: u64 },
+}
+impl Default for Backoff {
+  fn default() -> Self {
+    Self::Fixed { delay_ms: 0 }
+  }
+}
+
+impl Backoff {
+  /// 第`retry`次重试前的等待时长, 从1开始
+  pub fn delay(&self, retry: u32) -> Duration {
+    match self {
+      Backoff::Fixed { delay_ms } => Duration::from_millis(*delay_ms),
+      Backoff::Exponential {
+        delay_ms,
+        factor,
+        max_ms,
+      } => {
+        let ms = *delay_ms as f64 * factor.max(1.0).powi(retry.saturating_sub(1) as i32);
+        Duration::from_millis((ms as u64).min(*max_ms))
+      }
+    }
+  }
+}
+
+/// 中位数
+pub fn median(mut values: Vec<f64>) -> Option<f64> {
+  if values.is_empty() {
+    return None;
+  }
+  values.sort_by(|a, b| a.total_cmp(b));
+  let mid = values.len() / 2;
+  Some(match values.len() % 2 {
+    0 => (values[mid - 1] + values[mid]) / 2.0,
+    _ => values[mid],
+  })
+}
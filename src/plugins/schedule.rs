@@ -0,0 +1,179 @@
+use std::{
+  cmp::Reverse,
+  collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use serde_json::Value;
+
+use super::condition::{Expr, When};
+use crate::{
+  config::keys,
+  db::r#type::app::ExtendApp,
+  res::{Error, Result},
+};
+
+/// 按依赖和优先级排序已启用的步骤
+///
+/// - 同一层级内`priority`越小越先执行, 相同时按原顺序
+/// - 依赖不存在、`tag`重复或存在循环依赖时返回错误
+/// - 依赖已禁用的步骤时忽略该依赖
+/// - 执行条件引用的步骤视为依赖, 必须存在且已启用; 表达式需能解析
+/// - 变量引用的步骤(`#step:TAG#`及`capture`保存的`#name#`)视为依赖, 见`references`
+pub fn schedule(apps: Vec<ExtendApp>) -> Result<Vec<ExtendApp>> {
+  let mut tags = HashSet::new();
+  for app in &apps {
+    if !tags.insert(app.tag.as_str()) {
+      return Err(Error::Exists(format!("步骤标签重复: {}", app.tag).into()));
+    }
+  }
+  for app in &apps {
+    if let Some(dep) = app.depends_on.iter().find(|x| !tags.contains(x.as_str())) {
+      return Err(Error::NotFound(format!("[{}] 依赖不存在: {dep}", app.tag).into()));
+    }
+  }
+  let mut apps: Vec<ExtendApp> = apps.into_iter().filter(|x| x.enable).collect();
+  let index: HashMap<String, usize> = apps.iter().enumerate().map(|(i, x)| (x.tag.clone(), i)).collect();
+  for app in apps.iter_mut() {
+    app.depends_on.retain(|x| index.contains_key(x));
+  }
+  for app in &apps {
+    match &app.condition.when {
+      When::Passed { tag } | When::Failed { tag } => {
+        if *tag == app.tag {
+          return Err(Error::DataParse(format!("[{}] 执行条件不能引用自身", app.tag)));
+        }
+        if !tags.contains(tag.as_str()) {
+          return Err(Error::NotFound(format!("[{}] 条件步骤不存在: {tag}", app.tag).into()));
+        }
+        if !index.contains_key(tag) {
+          return Err(Error::DataParse(format!("[{}] 条件步骤未启用: {tag}", app.tag)));
+        }
+      }
+      When::Expr { expr } => {
+        expr
+          .parse::<Expr>()
+          .map_err(|e| Error::DataParse(format!("[{}] 执行条件 `{expr}`: {e}", app.tag)))?;
+      }
+      When::None => {}
+    }
+  }
+  let captures = captures(&apps);
+  let refs: Vec<Vec<String>> = apps.iter().map(|x| references(x, &captures)).collect();
+  for (app, refs) in apps.iter().zip(&refs) {
+    if let Some(tag) = refs.iter().find(|x| !index.contains_key(x.as_str())) {
+      return Err(match tags.contains(tag.as_str()) {
+        true => Error::DataParse(format!("[{}] 变量引用的步骤未启用: {tag}", app.tag)),
+        false => Error::NotFound(format!("[{}] 变量引用的步骤不存在: {tag}", app.tag).into()),
+      });
+    }
+  }
+  // Kahn 拓扑排序, 入度为0的步骤按 (priority, 原顺序) 出队
+  let mut degree: Vec<usize> = vec![0; apps.len()];
+  let mut next: Vec<Vec<usize>> = vec![vec![]; apps.len()];
+  for (i, app) in apps.iter().enumerate() {
+    let mut after: Vec<usize> = app.depends_on.iter().map(|x| index[x]).collect();
+    after.extend(app.condition.when.tag().map(|x| index[x]));
+    after.extend(refs[i].iter().map(|x| index[x]));
+    after.sort_unstable();
+    after.dedup();
+    degree[i] = after.len();
+    for dep in after {
+      next[dep].push(i);
+    }
+  }
+  let mut ready: BinaryHeap<Reverse<(i32, usize)>> = degree
+    .iter()
+    .enumerate()
+    .filter(|(_, d)| **d == 0)
+    .map(|(i, _)| Reverse((apps[i].priority, i)))
+    .collect();
+  let mut order = Vec::with_capacity(apps.len());
+  while let Some(Reverse((_, i))) = ready.pop() {
+    order.push(i);
+    for &n in &next[i] {
+      degree[n] -= 1;
+      if degree[n] == 0 {
+        ready.push(Reverse((apps[n].priority, n)));
+      }
+    }
+  }
+  if order.len() < apps.len() {
+    let cycle: Vec<&str> = degree
+      .iter()
+      .enumerate()
+      .filter(|(_, d)| **d > 0)
+      .map(|(i, _)| apps[i].tag.as_str())
+      .collect();
+    return Err(Error::DataParse(format!("存在循环依赖: {}", cycle.join(", "))));
+  }
+  let mut slots: Vec<Option<ExtendApp>> = apps.into_iter().map(Some).collect();
+  Ok(order.into_iter().filter_map(|i| slots[i].take()).collect())
+}
+
+/// 步骤`capture`保存的变量名到步骤`tag`
+pub fn captures(apps: &[ExtendApp]) -> HashMap<String, String> {
+  apps
+    .iter()
+    .filter_map(|x| Some((x.capture.clone().filter(|x| !x.is_empty())?, x.tag.clone())))
+    .collect()
+}
+
+/// JSON中所有字符串的`#key#`
+fn value_keys(value: &Value) -> Vec<&str> {
+  match value {
+    Value::String(s) => keys(s),
+    Value::Array(arr) => arr.iter().flat_map(value_keys).collect(),
+    Value::Object(obj) => obj.values().flat_map(value_keys).collect(),
+    _ => vec![],
+  }
+}
+
+/// 步骤执行前替换的变量所引用的步骤: `#step:TAG#`及`captures`中的`#name#`, 不含自身
+///
+/// 只用于等待, 不要求引用的步骤通过; 范围同`Template::apply`, 另含执行条件表达式
+pub fn references(app: &ExtendApp, captures: &HashMap<String, String>) -> Vec<String> {
+  let values = [
+    serde_json::to_value(&app.limits).unwrap_or_default(),
+    serde_json::to_value(&app.r#type).unwrap_or_default(),
+  ];
+  let expr = match &app.condition.when {
+    When::Expr { expr } => Some(expr),
+    _ => None,
+  };
+  let mut refs: Vec<String> = app
+    .cmd
+    .iter()
+    .chain(&app.cwd)
+    .chain(&app.res_url)
+    .chain(expr)
+    .flat_map(|x| keys(x))
+    .chain(values.iter().flat_map(value_keys))
+    .filter_map(|key| match key.strip_prefix("step:") {
+      Some(tag) => Some(tag.to_string()),
+      None => captures.get(key).cloned(),
+    })
+    .filter(|x| *x != app.tag)
+    .collect();
+  refs.sort_unstable();
+  refs.dedup();
+  refs
+}
+
+/// 按工站配置选取步骤, `tags`的顺序作为`priority`; `tags`为空时返回全部步骤
+pub fn select(apps: Vec<ExtendApp>, tags: &[String]) -> Result<Vec<ExtendApp>> {
+  if tags.is_empty() {
+    return Ok(apps);
+  }
+  let mut apps: HashMap<String, ExtendApp> = apps.into_iter().map(|x| (x.tag.clone(), x)).collect();
+  tags
+    .iter()
+    .enumerate()
+    .map(|(i, tag)| {
+      let mut app = apps
+        .remove(tag)
+        .ok_or(Error::NotFound(format!("工站步骤不存在或重复: {tag}").into()))?;
+      app.priority = i as i32;
+      Ok(app)
+    })
+    .collect()
+}
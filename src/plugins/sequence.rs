@@ -0,0 +1,599 @@
+use iced::{
+  futures::{channel::mpsc, SinkExt as _},
+  subscription, Subscription,
+};
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
+
+use e_utils::time::Utc;
+use serde_json::Value;
+use tokio::{
+  sync::{mpsc::unbounded_channel, Notify, Semaphore},
+  task::JoinSet,
+};
+
+use super::{
+  condition::{Expr, When},
+  control::RunControl,
+  executor::{LineTx, StepOutput, Stream},
+  filter,
+  hook::{self, Hooks},
+  limit::{as_f64, as_text, Violation},
+  report::{self, StepRow},
+  retry::{median, Aggregate, RetryMode},
+  schedule::{captures, references},
+  step,
+  template::Template,
+  upload,
+};
+use crate::{
+  config::logger::{log, Tag},
+  data::DB_SQLITE,
+  db::{
+    apis::run::{finish_run, insert_run, insert_step_result},
+    r#type::{
+      app::ExtendApp,
+      run::{TestRun, TestStepResult},
+    },
+    DbPool,
+  },
+  res::{PanicAny as _, Result},
+  ui::main_app::autotest::DataState,
+};
+
+/// 单次执行结果
+#[derive(Debug, Clone)]
+pub struct Attempt {
+  pub state: DataState,
+  pub res_value: Value,
+  pub violation: Option<Violation>,
+  pub output: Option<StepOutput>,
+}
+
+/// 步骤结果, 保留每一次执行记录
+#[derive(Debug, Clone)]
+pub struct StepResult {
+  pub state: DataState,
+  pub res_value: Value,
+  pub violation: Option<Violation>,
+  pub attempts: Vec<Attempt>,
+}
+
+/// 执行进度, `usize` 为表格行号
+#[derive(Debug, Clone)]
+pub enum Progress {
+  /// 测试记录已创建, 记录ID
+  Created(i64),
+  Started(usize),
+  /// 运行中步骤的一行输出
+  Output(usize, Stream, String),
+  Finished(usize, StepResult),
+  /// 全部结束, 总结果
+  Done(DataState),
+}
+
+/// 已完成步骤的状态, 用于等待依赖
+#[derive(Debug, Default, Clone)]
+struct Finished {
+  states: Arc<Mutex<HashMap<String, DataState>>>,
+  notify: Arc<Notify>,
+}
+impl Finished {
+  fn set(&self, tag: &str, state: DataState) {
+    self.states.lock().panic("sequence finished").insert(tag.to_string(), state);
+    self.notify.notify_waiters();
+  }
+
+  /// 总结果, 跳过的步骤不计入(依赖未通过时已有失败步骤)
+  fn result(&self) -> DataState {
+    let states = self.states.lock().panic("sequence finished");
+    if states.values().any(|x| *x == DataState::Aborted) {
+      DataState::Aborted
+    } else if states.values().any(|x| matches!(x, DataState::Fail | DataState::Timeout)) {
+      DataState::Fail
+    } else {
+      DataState::Success
+    }
+  }
+
+  /// 等待指定步骤全部完成, 返回其状态
+  async fn wait(&self, tags: &[String]) -> Vec<DataState> {
+    loop {
+      let notified = self.notify.notified();
+      {
+        let states = self.states.lock().panic("sequence finished");
+        let done: Option<Vec<DataState>> = tags.iter().map(|x| states.get(x).copied()).collect();
+        if let Some(done) = done {
+          return done;
+        }
+      }
+      notified.await;
+    }
+  }
+}
+
+/// 执行序列, 同一个`id`只会运行一次
+///
+/// `is_wait = false` 的步骤并行执行, 最多同时运行`max_parallel`个;
+/// `is_wait = true` 的步骤作为屏障, 等待之前的步骤全部完成后单独执行;
+/// 按`condition`判定是否执行, `always`的步骤在失败或中止后仍然执行;
+/// 执行记录保存到`test_run`/`test_step_result`; 通过`control`暂停、单步或中止;
+/// 执行前由`template`替换`cmd`、`cwd`、`res_url`中的变量, 先等待变量引用的步骤完成;
+/// `hooks.pre`失败时除`always`外的步骤均跳过, 总结果为失败; 结束后以`#result#`执行`hooks.post`;
+/// 步骤结果上传到`res_url`, 测试结果上传到工站的`upload_url`, 均先写入`outbox`;
+/// 结束后按`Config.report`生成测试报告
+pub fn run(
+  id: usize,
+  steps: Arc<Vec<ExtendApp>>,
+  max_parallel: usize,
+  run: TestRun,
+  control: RunControl,
+  template: Template,
+  hooks: Hooks,
+) -> Subscription<Progress> {
+  subscription::channel(id, 100, move |mut output| async move {
+    let pool = DB_SQLITE.read().await.pool();
+    let run_id = match insert_run(&pool, &run).await {
+      Ok(run_id) => {
+        template.set("run_id", run_id.to_string());
+        let _ = output.send(Progress::Created(run_id)).await;
+        Some(run_id)
+      }
+      Err(e) => {
+        log::error(format!("保存测试记录失败: {e}"), Tag::AutoTest);
+        None
+      }
+    };
+    let prepared = match hook::run("前置脚本", &hooks.pre, &template).await {
+      Ok(()) => true,
+      Err(e) => {
+        log::error(format!("前置脚本失败, 跳过测试步骤: {e}"), Tag::AutoTest);
+        false
+      }
+    };
+    let cfg = template.config();
+    let upload_steps = cfg.mes.upload_steps;
+    let upload_url = cfg.station().map(|x| x.upload_url.clone()).unwrap_or_default();
+    let run = TestRun {
+      id: run_id.unwrap_or_default(),
+      ..run
+    };
+    let results: Arc<Mutex<Vec<(usize, ExtendApp, StepResult)>>> = Default::default();
+    let finished = Finished::default();
+    let semaphore = Arc::new(Semaphore::new(max_parallel.max(1)));
+    let mut pending = JoinSet::new();
+    let captures = captures(&steps);
+    for (index, app) in steps.iter().cloned().enumerate() {
+      let refs = references(&app, &captures);
+      let is_wait = app.is_wait;
+      let mut tx: mpsc::Sender<Progress> = output.clone();
+      let finished = finished.clone();
+      let semaphore = semaphore.clone();
+      let pool = pool.clone();
+      let control = control.clone();
+      let template = template.clone();
+      let results = results.clone();
+      let run = run.clone();
+      let task = async move {
+        let always = app.condition.always;
+        let deps = finished.wait(&app.depends_on).await;
+        let deps_passed = deps.into_iter().all(|x| x == DataState::Success);
+        let when = condition(&app, &refs, &finished, &template).await;
+        let res = if control.is_aborted() && !always {
+          StepResult::aborted()
+        } else if (!prepared || !deps_passed) && !always {
+          // 前置脚本或前置步骤未通过时跳过
+          StepResult::skipped()
+        } else {
+          // 先取得运行名额再过暂停闸门, 暂停或单步时排队的步骤不会在名额空出后继续启动
+          let _permit = semaphore.acquire_owned().await;
+          if !control.gate().await && !always {
+            StepResult::aborted()
+          } else {
+            // 依赖完成后再替换, 以便引用其结果
+            match when.and_then(|run| run.then(|| template.apply(&app)).transpose()) {
+              Err(e) => {
+                log::error(format!("[{}] 执行条件或变量替换失败: {e}", app.tag), Tag::AutoTest);
+                StepResult::error(e.to_string())
+              }
+              Ok(None) => {
+                log::info(format!("[{}] 不满足执行条件, 跳过", app.tag), Tag::AutoTest);
+                StepResult::skipped()
+              }
+              Ok(Some(applied)) => {
+                // 总是执行的步骤不受中止影响
+                let control = match always {
+                  true => RunControl::default(),
+                  false => control.clone(),
+                };
+                let _ = tx.send(Progress::Started(index)).await;
+                let (lines, mut rx) = unbounded_channel();
+                let mut out = tx.clone();
+                let forward = tokio::spawn(async move {
+                  while let Some((stream, line)) = rx.recv().await {
+                    let _ = out.send(Progress::Output(index, stream, line)).await;
+                  }
+                });
+                let res = run_step(&applied, &control, Some(lines)).await;
+                // 输出先于结果送达
+                let _ = forward.await;
+                res
+              }
+            }
+          }
+        };
+        template.set(format!("step:{}", app.tag), as_text(&res.res_value));
+        if let Some(name) = app.capture.as_ref().filter(|x| !x.is_empty()) {
+          if res.state == DataState::Success {
+            let value = as_text(&res.res_value);
+            log::info(format!("[{}] #{name}# = {value}", app.tag), Tag::AutoTest);
+            template.set(name.clone(), value);
+          }
+        }
+        finished.set(&app.tag, res.state);
+        if let Some(run_id) = run_id {
+          record(&pool, run_id, &app, &res).await;
+        }
+        if let Some(url) = app.res_url.as_ref().filter(|x| upload_steps && !x.is_empty()) {
+          match template.render(url) {
+            Ok(url) => upload::enqueue(&pool, &url, &upload::step_payload(&run, &app, &res)).await,
+            Err(e) => log::error(format!("[{}] 上传地址变量替换失败: {e}", app.tag), Tag::AutoTest),
+          }
+        }
+        let _ = tx.send(Progress::Finished(index, res.clone())).await;
+        results.lock().panic("sequence results").push((index, app, res));
+      };
+      if is_wait {
+        while pending.join_next().await.is_some() {}
+        task.await;
+      } else {
+        pending.spawn(task);
+      }
+    }
+    while pending.join_next().await.is_some() {}
+    let result = match prepared {
+      true => finished.result(),
+      false => DataState::Fail,
+    };
+    template.set("result", result.as_str());
+    if let Err(e) = hook::run("后置脚本", &hooks.post, &template).await {
+      log::error(format!("后置脚本失败: {e}"), Tag::AutoTest);
+    }
+    let ended_at = Utc::now();
+    let run = TestRun {
+      result: result.as_str().to_string(),
+      ended_at: Some(ended_at),
+      vars: template.to_value(),
+      ..run
+    };
+    if let Some(run_id) = run_id {
+      if let Err(e) = finish_run(&pool, run_id, &run.result, ended_at, &run.vars).await {
+        log::error(format!("保存测试结果失败: {e}"), Tag::AutoTest);
+      }
+    }
+    let mut results = std::mem::take(&mut *results.lock().panic("sequence results"));
+    results.sort_by_key(|(index, ..)| *index);
+    if !upload_url.is_empty() {
+      let steps = results.iter().map(|(_, app, res)| upload::step_payload(&run, app, res)).collect();
+      let payload = upload::run_payload(&run, steps);
+      match template.render(&upload_url) {
+        Ok(url) => upload::enqueue(&pool, &url, &payload).await,
+        Err(e) => log::error(format!("测试结果上传地址变量替换失败: {e}"), Tag::AutoTest),
+      }
+    }
+    if cfg.report.enabled() {
+      let rows: Vec<StepRow> = results.iter().map(|(_, app, res)| StepRow::new(app, res)).collect();
+      match report::write(&template, &run, &rows).await {
+        Ok(files) => {
+          for file in files {
+            log::info(format!("测试报告: {}", file.display()), Tag::AutoTest);
+          }
+        }
+        Err(e) => log::error(format!("生成测试报告失败: {e}"), Tag::AutoTest),
+      }
+    }
+    let _ = output.send(Progress::Done(result)).await;
+    loop {
+      iced::futures::future::pending::<()>().await;
+    }
+  })
+}
+
+/// 等待变量引用的步骤完成后判定执行条件; 引用的步骤只等待完成, 不要求通过
+async fn condition(app: &ExtendApp, refs: &[String], finished: &Finished, template: &Template) -> Result<bool> {
+  finished.wait(refs).await;
+  match &app.condition.when {
+    When::None => Ok(true),
+    When::Passed { tag } | When::Failed { tag } => {
+      let states = finished.wait(std::slice::from_ref(tag)).await;
+      Ok(states.into_iter().all(|x| app.condition.when.matches(x)))
+    }
+    When::Expr { expr } => expr.parse::<Expr>().and_then(|x| x.eval(|v| template.render(v))),
+  }
+}
+
+impl StepResult {
+  /// 因前置步骤未通过而跳过
+  pub fn skipped() -> Self {
+    Self {
+      state: DataState::Skipped,
+      res_value: Value::Null,
+      violation: None,
+      attempts: vec![],
+    }
+  }
+
+  /// 未执行即失败, 如变量替换失败
+  pub fn error(msg: String) -> Self {
+    Self {
+      state: DataState::Fail,
+      res_value: Value::String(msg),
+      ..Self::skipped()
+    }
+  }
+
+  /// 被中止
+  pub fn aborted() -> Self {
+    Self {
+      state: DataState::Aborted,
+      ..Self::skipped()
+    }
+  }
+
+  /// 最后一次执行的输出
+  pub fn output(&self) -> Option<&StepOutput> {
+    self.attempts.last().and_then(|x| x.output.as_ref())
+  }
+}
+
+/// 执行单个步骤
+///
+/// `is_repeat`时按`retry`策略最多执行`count`次:
+/// `UntilPass`通过即停止; `Repeat`执行满`count`次后按`aggregate`汇总;
+/// 中止时结束正在执行的进程树; `lines`不为空时实时转发输出
+pub async fn run_step(app: &ExtendApp, control: &RunControl, lines: Option<LineTx>) -> StepResult {
+  let ref retry = app.retry;
+  let total = app.attempts();
+  let mut attempts: Vec<Attempt> = vec![];
+  for n in 1..=total {
+    let attempt = tokio::select! {
+      attempt = async {
+        if n > 1 {
+          tokio::time::sleep(retry.backoff.delay(n - 1)).await;
+        }
+        run_once(app, lines.clone()).await
+      } => attempt,
+      _ = control.aborted() => {
+        log::warn(format!("[{}] 已中止", app.tag), Tag::AutoTest);
+        return StepResult {
+          attempts,
+          ..StepResult::aborted()
+        };
+      }
+    };
+    log::debug(
+      format!("[{}] 第{n}/{total}次 {:?} {}", app.tag, attempt.state, attempt.res_value),
+      Tag::AutoTest,
+    );
+    let pass = attempt.state == DataState::Success;
+    attempts.push(attempt);
+    if pass && (retry.mode == RetryMode::UntilPass || !app.is_repeat) {
+      break;
+    }
+  }
+  let mut res = match (app.is_repeat && retry.mode == RetryMode::Repeat, retry.aggregate) {
+    (true, Aggregate::AnyPass) => {
+      let last = attempts.iter().rev().find(|x| x.state == DataState::Success);
+      from_attempt(last.or(attempts.last()))
+    }
+    (true, Aggregate::Median) => {
+      let values: Option<Vec<f64>> = attempts.iter().map(|x| as_f64(&x.res_value)).collect();
+      match values.and_then(median) {
+        Some(m) => {
+          let res_value = Value::from(m);
+          let violation = judge(app, &res_value).err();
+          let executed = attempts.iter().all(|x| x.output.as_ref().is_some_and(StepOutput::success));
+          StepResult {
+            state: if executed && violation.is_none() {
+              DataState::Success
+            } else {
+              DataState::Fail
+            },
+            res_value,
+            violation,
+            attempts: vec![],
+          }
+        }
+        None => StepResult {
+          state: DataState::Fail,
+          res_value: Value::Null,
+          violation: Some(Violation::NotNumber),
+          attempts: vec![],
+        },
+      }
+    }
+    (true, Aggregate::AllPass) => {
+      let failed = attempts.iter().find(|x| x.state != DataState::Success);
+      from_attempt(failed.or(attempts.last()))
+    }
+    // UntilPass: 取最后一次
+    (false, _) => from_attempt(attempts.last()),
+  };
+  if res.state == DataState::Success && attempts.iter().any(|x| x.state != DataState::Success) {
+    log::warn(
+      format!("[{}] 共执行{}次, 其中有失败记录", app.tag, attempts.len()),
+      Tag::AutoTest,
+    );
+  }
+  res.attempts = attempts;
+  res
+}
+
+fn from_attempt(attempt: Option<&Attempt>) -> StepResult {
+  match attempt {
+    Some(x) => StepResult {
+      state: x.state,
+      res_value: x.res_value.clone(),
+      violation: x.violation.clone(),
+      attempts: vec![],
+    },
+    None => StepResult {
+      state: DataState::Fail,
+      res_value: Value::Null,
+      violation: None,
+      attempts: vec![],
+    },
+  }
+}
+
+/// 执行一次并判定
+async fn run_once(app: &ExtendApp, lines: Option<LineTx>) -> Attempt {
+  match step::run(app, lines).await {
+    // 超时: 保留部分输出, 不再判定
+    Ok(output) if output.timeout => Attempt {
+      state: DataState::Timeout,
+      res_value: Value::String(format!("超时 {}s", app.timeout)),
+      violation: None,
+      output: Some(output),
+    },
+    Ok(output) => match filter::extract(&app.filter, &output.stdout) {
+      Ok(v) => {
+        let violation = judge(app, &v).err();
+        Attempt {
+          state: if output.success() && violation.is_none() {
+            DataState::Success
+          } else {
+            DataState::Fail
+          },
+          res_value: v,
+          violation,
+          output: Some(output),
+        }
+      }
+      Err(e) => Attempt {
+        state: DataState::Fail,
+        res_value: Value::String(e.to_string()),
+        violation: None,
+        output: Some(output),
+      },
+    },
+    Err(e) => {
+      log::error(format!("[{}] 执行失败: {e}", app.tag), Tag::AutoTest);
+      Attempt {
+        state: DataState::Fail,
+        res_value: Value::String(e.to_string()),
+        violation: None,
+        output: None,
+      }
+    }
+  }
+}
+
+/// 保存步骤记录, 每次执行一行, 跳过的步骤记为第0次
+async fn record(pool: &DbPool, run_id: i64, app: &ExtendApp, res: &StepResult) {
+  let base = TestStepResult {
+    run_id,
+    app_id: app.id,
+    tag: app.tag.clone(),
+    label: app.label.clone(),
+    limits: serde_json::to_value(&app.limits).unwrap_or_default(),
+    ..Default::default()
+  };
+  let rows: Vec<TestStepResult> = match res.attempts.is_empty() {
+    true => vec![TestStepResult {
+      state: res.state.as_str().to_string(),
+      res_value: res.res_value.clone(),
+      violation: res.violation.as_ref().map(|x| x.to_string()),
+      ..base
+    }],
+    false => res
+      .attempts
+      .iter()
+      .enumerate()
+      .map(|(i, x)| {
+        let output = x.output.clone().unwrap_or_default();
+        TestStepResult {
+          attempt: i as i32 + 1,
+          state: x.state.as_str().to_string(),
+          stdout: output.stdout,
+          stderr: output.stderr,
+          res_value: x.res_value.clone(),
+          violation: x.violation.as_ref().map(|x| x.to_string()),
+          duration_ms: output.elapsed.as_millis() as i64,
+          ..base.clone()
+        }
+      })
+      .collect(),
+  };
+  for row in rows {
+    if let Err(e) = insert_step_result(pool, &row).await {
+      log::error(format!("[{}] 保存步骤记录失败: {e}", app.tag), Tag::AutoTest);
+    }
+  }
+}
+
+/// 判定结果, `is_check`时结果值需满足限值
+fn judge(app: &ExtendApp, res_value: &Value) -> Result<(), Violation> {
+  match app.is_check {
+    true => app.limits.check(res_value),
+    false => Ok(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::*;
+  use crate::{config::Config, plugins::condition::Condition};
+
+  fn app(tag: &str, when: When) -> ExtendApp {
+    ExtendApp {
+      tag: tag.to_string(),
+      enable: true,
+      condition: Condition { when, always: false },
+      ..Default::default()
+    }
+  }
+
+  /// 表达式引用的步骤不在`depends_on`中, 并行执行时也须等待其完成
+  #[tokio::test]
+  async fn expr_waits_for_referenced_step() {
+    let producer = app("VOLT", When::None);
+    let consumer = app(
+      "CHECK",
+      When::Expr {
+        expr: "#step:VOLT# > 3.3".to_string(),
+      },
+    );
+    let apps = vec![producer.clone(), consumer.clone()];
+    let refs = references(&consumer, &captures(&apps));
+    assert_eq!(refs, vec!["VOLT".to_string()]);
+    let finished = Finished::default();
+    let template = Template::new(Config::default(), HashMap::new());
+    // max_parallel = 2: 两个步骤同时运行
+    let semaphore = Arc::new(Semaphore::new(2));
+    let mut pending = JoinSet::new();
+    {
+      let (finished, template, semaphore) = (finished.clone(), template.clone(), semaphore.clone());
+      pending.spawn(async move {
+        let _permit = semaphore.acquire_owned().await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        template.set("step:VOLT", "5.0");
+        finished.set("VOLT", DataState::Success);
+        None
+      });
+    }
+    pending.spawn(async move {
+      let _permit = semaphore.acquire_owned().await;
+      Some(condition(&consumer, &refs, &finished, &template).await)
+    });
+    let mut results = vec![];
+    while let Some(res) = pending.join_next().await {
+      results.extend(res.unwrap());
+    }
+    assert!(matches!(results.as_slice(), [Ok(true)]));
+  }
+}
@@ -0,0 +1,247 @@
+//! 统计过程控制: 均值、标准差、Cp/Cpk, X-bar/R 控制图及 Western Electric 判异规则
+use std::fmt::{self, Display};
+
+use super::{
+  limit::{as_f64, Limit},
+  validate::{Level, Report},
+};
+use crate::{
+  config::logger::{log, Tag},
+  data::DB_SQLITE,
+  db::apis::run::select_step_history,
+  res::Result,
+};
+
+/// 滚动窗口, 最近的测试次数
+pub const WINDOW: i64 = 125;
+/// 子组大小, 连续的测试按此分组
+pub const SUBGROUP: usize = 5;
+/// 子组大小为 5 时的控制图系数
+const A2: f64 = 0.577;
+const D2: f64 = 2.326;
+const D3: f64 = 0.0;
+const D4: f64 = 2.114;
+/// 计算控制限至少需要的子组数
+const MIN_SUBGROUPS: usize = 2;
+
+/// Western Electric 判异规则, 另加极差超限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+  /// 1 点落在 3σ 以外
+  One,
+  /// 连续 3 点中有 2 点落在同侧 2σ 以外
+  Two,
+  /// 连续 5 点中有 4 点落在同侧 1σ 以外
+  Three,
+  /// 连续 8 点落在中心线同侧
+  Four,
+  /// 极差超出控制限
+  Range,
+}
+impl Display for Rule {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Rule::One => write!(f, "规则1: 超出3σ控制限"),
+      Rule::Two => write!(f, "规则2: 连续3点中2点在同侧2σ外"),
+      Rule::Three => write!(f, "规则3: 连续5点中4点在同侧1σ外"),
+      Rule::Four => write!(f, "规则4: 连续8点在中心线同侧"),
+      Rule::Range => write!(f, "极差超出控制限"),
+    }
+  }
+}
+
+/// 子组
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subgroup {
+  /// 子组最后一次测试的记录ID
+  pub run_id: i64,
+  pub mean: f64,
+  pub range: f64,
+}
+
+/// 判异结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signal {
+  /// 子组序号
+  pub index: usize,
+  pub rule: Rule,
+}
+impl Display for Signal {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "第{}组 {}", self.index + 1, self.rule)
+  }
+}
+
+/// 步骤的过程能力及控制图
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Spc {
+  pub tag: String,
+  /// 参与计算的测试次数
+  pub count: usize,
+  pub mean: f64,
+  /// 样本标准差
+  pub sigma: f64,
+  /// 组内标准差 `R̄/d2`, 用于 Cp/Cpk
+  pub sigma_within: f64,
+  /// 规格下限、上限
+  pub low: Option<f64>,
+  pub high: Option<f64>,
+  pub cp: Option<f64>,
+  pub cpk: Option<f64>,
+  pub subgroups: Vec<Subgroup>,
+  /// X-bar 图中心线及控制限
+  pub center: f64,
+  pub ucl: f64,
+  pub lcl: f64,
+  /// R 图中心线及控制限
+  pub r_bar: f64,
+  pub r_ucl: f64,
+  pub r_lcl: f64,
+  pub signals: Vec<Signal>,
+}
+
+impl Spc {
+  /// 按`(记录ID, 结果值)`时间正序计算; 从最新一次往前分组, 不足一组的最早数据舍弃
+  ///
+  /// 子组不足`MIN_SUBGROUPS`时返回`None`
+  pub fn compute(tag: impl Into<String>, values: &[(i64, f64)], limit: &Limit) -> Option<Self> {
+    let values = &values[values.len() % SUBGROUP..];
+    let subgroups: Vec<Subgroup> = values
+      .chunks(SUBGROUP)
+      .map(|chunk| {
+        let (min, max) = chunk.iter().fold((f64::MAX, f64::MIN), |(min, max), x| (min.min(x.1), max.max(x.1)));
+        Subgroup {
+          run_id: chunk[chunk.len() - 1].0,
+          mean: chunk.iter().map(|x| x.1).sum::<f64>() / chunk.len() as f64,
+          range: max - min,
+        }
+      })
+      .collect();
+    if subgroups.len() < MIN_SUBGROUPS {
+      return None;
+    }
+    let count = values.len();
+    let mean = values.iter().map(|x| x.1).sum::<f64>() / count as f64;
+    let sigma = (values.iter().map(|x| (x.1 - mean).powi(2)).sum::<f64>() / (count - 1) as f64).sqrt();
+    let center = subgroups.iter().map(|x| x.mean).sum::<f64>() / subgroups.len() as f64;
+    let r_bar = subgroups.iter().map(|x| x.range).sum::<f64>() / subgroups.len() as f64;
+    let sigma_within = r_bar / D2;
+    let (low, high) = limit.bounds();
+    let capable = |v: f64| (sigma_within > 0.0).then_some(v);
+    let cp = match (low, high) {
+      (Some(low), Some(high)) => capable((high - low) / (6.0 * sigma_within)),
+      _ => None,
+    };
+    let cpk = [
+      high.map(|high| (high - center) / (3.0 * sigma_within)),
+      low.map(|low| (center - low) / (3.0 * sigma_within)),
+    ]
+    .into_iter()
+    .flatten()
+    .reduce(f64::min)
+    .and_then(capable);
+    let mut slf = Self {
+      tag: tag.into(),
+      count,
+      mean,
+      sigma,
+      sigma_within,
+      low,
+      high,
+      cp,
+      cpk,
+      subgroups,
+      center,
+      ucl: center + A2 * r_bar,
+      lcl: center - A2 * r_bar,
+      r_bar,
+      r_ucl: D4 * r_bar,
+      r_lcl: D3 * r_bar,
+      signals: vec![],
+    };
+    slf.signals = slf.rules();
+    Some(slf)
+  }
+
+  /// X-bar 图的 1σ 区间宽度
+  pub fn zone(&self) -> f64 {
+    (self.ucl - self.center) / 3.0
+  }
+
+  /// 逐点判异, 每条规则在满足条件的最后一点上标记
+  fn rules(&self) -> Vec<Signal> {
+    let zone = self.zone();
+    let deviations: Vec<f64> = self.subgroups.iter().map(|x| x.mean - self.center).collect();
+    // 最近`n`点中至少`k`点与当前点同侧且超出`zones`个σ, 当前点也须超出
+    let beyond = |i: usize, n: usize, k: usize, zones: f64| {
+      let limit = zone * zones;
+      let side = deviations[i].signum();
+      i + 1 >= n
+        && deviations[i].abs() > limit
+        && deviations[i + 1 - n..=i].iter().filter(|x| x.signum() == side && x.abs() > limit).count() >= k
+    };
+    let mut signals = vec![];
+    for (i, group) in self.subgroups.iter().enumerate() {
+      let mut push = |rule| signals.push(Signal { index: i, rule });
+      if zone > 0.0 {
+        if beyond(i, 1, 1, 3.0) {
+          push(Rule::One);
+        }
+        if beyond(i, 3, 2, 2.0) {
+          push(Rule::Two);
+        }
+        if beyond(i, 5, 4, 1.0) {
+          push(Rule::Three);
+        }
+      }
+      if beyond(i, 8, 8, 0.0) {
+        push(Rule::Four);
+      }
+      if group.range > self.r_ucl || group.range < self.r_lcl {
+        push(Rule::Range);
+      }
+    }
+    signals
+  }
+
+  /// 最新子组触发的判异
+  pub fn latest(&self) -> impl Iterator<Item = &Signal> {
+    let last = self.subgroups.len().saturating_sub(1);
+    self.signals.iter().filter(move |x| x.index == last)
+  }
+}
+
+/// 格式化可选的能力指数
+pub fn format_index(v: Option<f64>) -> String {
+  v.map(|x| format!("{x:.2}")).unwrap_or_else(|| "-".to_string())
+}
+
+/// 读取步骤最近`WINDOW`次的数值结果计算, 规格限取最新记录的限值; `station`为空时不限工站
+pub async fn load(tag: String, station: String) -> Result<Option<Spc>> {
+  let pool = DB_SQLITE.read().await.pool();
+  let rows = select_step_history(&pool, &tag, &station, WINDOW).await?;
+  let limit = rows
+    .last()
+    .and_then(|x| serde_json::from_value::<Limit>(x.limits.clone()).ok())
+    .unwrap_or_default();
+  let values: Vec<(i64, f64)> = rows.iter().filter_map(|x| Some((x.run_id, as_f64(&x.res_value)?))).collect();
+  Ok(Spc::compute(tag, &values, &limit))
+}
+
+/// 测试结束后检查步骤, 最新子组触发判异时给出警告; 统计失败只写日志
+pub async fn check(tags: Vec<String>, station: String) -> Report {
+  let mut report = Report::default();
+  for tag in tags {
+    match load(tag.clone(), station.clone()).await {
+      Ok(Some(spc)) => {
+        for signal in spc.latest() {
+          let message = format!("{} 均值={:.4} Cpk={}", signal.rule, spc.center, format_index(spc.cpk));
+          report.push(Level::Warning, tag.as_str(), message);
+        }
+      }
+      Ok(None) => {}
+      Err(e) => log::error(format!("[{tag}] SPC统计失败: {e}"), Tag::AutoTest),
+    }
+  }
+  report
+}
@@ -0,0 +1,113 @@
+//! 良率统计: 直通率、最终良率、复测率
+use std::collections::BTreeMap;
+
+use crate::db::r#type::run::TestRun;
+
+/// 合计行的日期
+pub const TOTAL: &str = "合计";
+
+/// 工站某天的良率
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Yield {
+  /// `YYYY-MM-DD`(UTC), 合计为`TOTAL`
+  pub day: String,
+  pub station: String,
+  /// 测试的 SN 数量
+  pub units: usize,
+  /// 测试次数
+  pub runs: usize,
+  /// 首次测试即通过的 SN 数量
+  pub first_pass: usize,
+  /// 最后一次测试通过的 SN 数量
+  pub final_pass: usize,
+}
+impl Yield {
+  fn ratio(n: usize, total: usize) -> f64 {
+    match total {
+      0 => 0.0,
+      total => n as f64 / total as f64,
+    }
+  }
+
+  /// 直通率
+  pub fn first_pass_yield(&self) -> f64 {
+    Self::ratio(self.first_pass, self.units)
+  }
+
+  /// 最终良率
+  pub fn final_yield(&self) -> f64 {
+    Self::ratio(self.final_pass, self.units)
+  }
+
+  /// 复测率, 复测次数占测试次数的比例
+  pub fn retest_rate(&self) -> f64 {
+    Self::ratio(self.runs - self.units, self.runs)
+  }
+}
+
+/// 按工站、日期统计, 每个工站另有一行合计; 未结束的测试记录不计入
+///
+/// 同一 SN 在整个查询范围内按开始时间排序, 第一次为首测、最后一次为最终结果;
+/// 该 SN 的全部测试计入首测当天, 跨天复测不会在次日被当作首测
+pub fn yields(runs: &[TestRun]) -> Vec<Yield> {
+  let mut runs: Vec<&TestRun> = runs.iter().filter(|x| x.ended_at.is_some()).collect();
+  runs.sort_by_key(|x| (x.started_at, x.id));
+  // (工站, SN) -> 测试记录
+  let mut units: BTreeMap<(&str, &str), Vec<&TestRun>> = BTreeMap::new();
+  for run in runs {
+    units.entry((run.station.as_str(), run.sn.as_str())).or_default().push(run);
+  }
+  let pass = |run: Option<&&TestRun>| run.is_some_and(|x| x.result == "Pass");
+  let mut groups: BTreeMap<(String, String), Yield> = BTreeMap::new();
+  for ((station, _), runs) in units {
+    let day = runs[0].started_at.format("%Y-%m-%d").to_string();
+    for day in [day, TOTAL.to_string()] {
+      let group = groups.entry((station.to_string(), day.clone())).or_insert_with(|| Yield {
+        day,
+        station: station.to_string(),
+        ..Default::default()
+      });
+      group.units += 1;
+      group.runs += runs.len();
+      group.first_pass += pass(runs.first()) as usize;
+      group.final_pass += pass(runs.last()) as usize;
+    }
+  }
+  groups.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use e_utils::time::{DateTime, Utc};
+
+  use super::*;
+
+  fn run(id: i64, sn: &str, result: &str, ts: i64) -> TestRun {
+    let started_at = DateTime::<Utc>::from_timestamp(ts, 0).unwrap();
+    TestRun {
+      id,
+      sn: sn.to_string(),
+      station: "ST1".to_string(),
+      result: result.to_string(),
+      started_at,
+      ended_at: Some(started_at),
+      ..Default::default()
+    }
+  }
+
+  /// 前一天失败、次日复测通过: 计入首测当天, 不算直通
+  #[test]
+  fn retest_on_next_day() {
+    // 2024-01-01 23:00 及 2024-01-02 01:00 (UTC)
+    let (day1, day2) = (1704150000, 1704157200);
+    let runs = vec![run(1, "A", "Fail", day1), run(2, "A", "Pass", day2), run(3, "B", "Pass", day2)];
+    let yields = yields(&runs);
+    let find = |day: &str| yields.iter().find(|x| x.day == day).unwrap();
+    let first = find("2024-01-01");
+    assert_eq!((first.units, first.runs, first.first_pass, first.final_pass), (1, 2, 0, 1));
+    let second = find("2024-01-02");
+    assert_eq!((second.units, second.runs, second.first_pass, second.final_pass), (1, 1, 1, 1));
+    let total = find(TOTAL);
+    assert_eq!((total.units, total.runs, total.first_pass, total.final_pass), (2, 3, 1, 2));
+  }
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use super::{output, StepRunner};
+use crate::{
+  db::r#type::app::ExtendApp,
+  plugins::executor::{LineTx, StepOutput},
+  res::Result,
+};
+
+/// 延时, 如等待上电稳定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DelayStep {
+  pub delay_ms: u64,
+}
+
+impl StepRunner for DelayStep {
+  async fn run(&self, _app: &ExtendApp, _lines: Option<LineTx>) -> Result<StepOutput> {
+    let start = Instant::now();
+    tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+    Ok(output(true, self.delay_ms.to_string(), "", start))
+  }
+}
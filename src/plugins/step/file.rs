@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use tokio::time::Instant;
+
+use super::{output, StepRunner};
+use crate::{
+  db::r#type::app::ExtendApp,
+  plugins::executor::{work_dir, LineTx, StepOutput},
+  res::Result,
+};
+
+/// 文件检查, 相对路径基于步骤的工作目录
+///
+/// 未设置`sha256`时只检查文件存在, 输出为文件大小;
+/// 设置时输出为文件的 SHA-256, 与之相同(不区分大小写)视为成功
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FileStep {
+  pub path: String,
+  pub sha256: Option<String>,
+}
+
+impl StepRunner for FileStep {
+  async fn run(&self, app: &ExtendApp, _lines: Option<LineTx>) -> Result<StepOutput> {
+    let start = Instant::now();
+    let path = work_dir(app).join(&self.path);
+    let meta = match tokio::fs::metadata(&path).await {
+      Ok(meta) if meta.is_file() => meta,
+      _ => return Ok(output(false, "", format!("文件不存在: {}", path.display()), start)),
+    };
+    Ok(match &self.sha256 {
+      Some(expect) => {
+        let digest = format!("{:x}", Sha256::digest(tokio::fs::read(&path).await?));
+        let ok = digest.eq_ignore_ascii_case(expect.trim());
+        output(ok, digest, "", start)
+      }
+      None => output(true, meta.len().to_string(), "", start),
+    })
+  }
+}
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use super::{output, StepRunner};
+use crate::{
+  db::r#type::app::ExtendApp,
+  plugins::executor::{LineTx, StepOutput},
+  res::{Error, Result},
+};
+
+/// HTTP 请求, 响应体作为输出; 状态码等于`expect_status`(未设置时为2xx)视为成功
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HttpStep {
+  pub method: String,
+  pub url: String,
+  pub headers: BTreeMap<String, String>,
+  pub body: Option<String>,
+  pub expect_status: Option<u16>,
+}
+impl Default for HttpStep {
+  fn default() -> Self {
+    Self {
+      method: "GET".to_string(),
+      url: String::new(),
+      headers: BTreeMap::new(),
+      body: None,
+      expect_status: None,
+    }
+  }
+}
+
+impl StepRunner for HttpStep {
+  async fn run(&self, _app: &ExtendApp, _lines: Option<LineTx>) -> Result<StepOutput> {
+    let start = Instant::now();
+    let method = reqwest::Method::from_bytes(self.method.to_uppercase().as_bytes())
+      .map_err(|_| Error::DataParse(format!("不支持的请求方法: {}", self.method)))?;
+    let mut req = reqwest::Client::new().request(method, &self.url);
+    for (k, v) in &self.headers {
+      req = req.header(k, v);
+    }
+    if let Some(body) = &self.body {
+      req = req.body(body.clone());
+    }
+    let res = req.send().await?;
+    let status = res.status();
+    let ok = match self.expect_status {
+      Some(expect) => status.as_u16() == expect,
+      None => status.is_success(),
+    };
+    let body = res.text().await?;
+    Ok(output(ok, body, format!("HTTP {status}"), start))
+  }
+}
@@ -0,0 +1,151 @@
+//! 步骤类型, 存储于`externApp.type`(编号)和`externApp.params`(参数)
+mod delay;
+mod file;
+mod http;
+mod process;
+pub mod prompt;
+mod shell;
+mod tcp;
+use std::{
+  fmt::{self, Display},
+  future::Future,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::time::Instant;
+
+pub use self::{
+  delay::DelayStep, file::FileStep, http::HttpStep, process::ProcessStep, prompt::PromptStep, shell::ShellStep,
+  tcp::TcpStep,
+};
+use super::executor::{LineTx, StepOutput};
+use crate::{db::r#type::app::ExtendApp, res::Result};
+
+/// 步骤执行器, 每种步骤类型实现一个
+pub trait StepRunner {
+  /// 执行一次, `stdout`用于`filter`提取结果值, 返回码为0表示执行成功
+  fn run(&self, app: &ExtendApp, lines: Option<LineTx>) -> impl Future<Output = Result<StepOutput>> + Send;
+}
+
+/// 步骤类型及其参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StepKind {
+  /// 外部进程, 执行`cmd`
+  Process(ProcessStep),
+  /// 脚本
+  Shell(ShellStep),
+  /// 延时
+  Delay(DelayStep),
+  /// 操作员确认
+  Prompt(PromptStep),
+  /// HTTP 请求
+  Http(HttpStep),
+  /// 文件存在及校验和
+  File(FileStep),
+  /// TCP 端口探测
+  Tcp(TcpStep),
+}
+impl Default for StepKind {
+  fn default() -> Self {
+    Self::Process(ProcessStep {})
+  }
+}
+
+impl Display for StepKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = match self {
+      StepKind::Process(_) => "进程",
+      StepKind::Shell(_) => "脚本",
+      StepKind::Delay(_) => "延时",
+      StepKind::Prompt(_) => "提示",
+      StepKind::Http(_) => "HTTP",
+      StepKind::File(_) => "文件",
+      StepKind::Tcp(_) => "TCP",
+    };
+    write!(f, "{s}")
+  }
+}
+
+impl StepKind {
+  /// 存储的编号
+  pub fn code(&self) -> i32 {
+    match self {
+      StepKind::Process(_) => 0,
+      StepKind::Shell(_) => 1,
+      StepKind::Delay(_) => 2,
+      StepKind::Prompt(_) => 3,
+      StepKind::Http(_) => 4,
+      StepKind::File(_) => 5,
+      StepKind::Tcp(_) => 6,
+    }
+  }
+
+  /// 编号对应的默认参数, 未知编号视为外部进程
+  pub fn from_code(code: i32) -> Self {
+    match code {
+      1 => StepKind::Shell(Default::default()),
+      2 => StepKind::Delay(Default::default()),
+      3 => StepKind::Prompt(Default::default()),
+      4 => StepKind::Http(Default::default()),
+      5 => StepKind::File(Default::default()),
+      6 => StepKind::Tcp(Default::default()),
+      _ => StepKind::default(),
+    }
+  }
+
+  /// 从数据库读取, 参数与编号不一致时使用编号的默认参数
+  pub fn from_db(code: i32, params: Value) -> Self {
+    serde_json::from_value::<StepKind>(params)
+      .ok()
+      .filter(|x| x.code() == code)
+      .unwrap_or_else(|| Self::from_code(code))
+  }
+
+  /// 是否自行处理超时(进程类需要结束进程树并保留输出)
+  fn handles_timeout(&self) -> bool {
+    matches!(self, StepKind::Process(_) | StepKind::Shell(_))
+  }
+}
+
+/// 按步骤类型分发执行, 超时由各类型自行处理或在此统一处理
+pub async fn run(app: &ExtendApp, lines: Option<LineTx>) -> Result<StepOutput> {
+  let ref kind = app.r#type;
+  let fut = async {
+    match kind {
+      StepKind::Process(x) => x.run(app, lines).await,
+      StepKind::Shell(x) => x.run(app, lines).await,
+      StepKind::Delay(x) => x.run(app, lines).await,
+      StepKind::Prompt(x) => x.run(app, lines).await,
+      StepKind::Http(x) => x.run(app, lines).await,
+      StepKind::File(x) => x.run(app, lines).await,
+      StepKind::Tcp(x) => x.run(app, lines).await,
+    }
+  };
+  match app.timeout_duration().filter(|_| !kind.handles_timeout()) {
+    Some(duration) => {
+      let start = Instant::now();
+      match tokio::time::timeout(duration, fut).await {
+        Ok(res) => res,
+        Err(_) => Ok(StepOutput {
+          timeout: true,
+          elapsed: start.elapsed(),
+          ..Default::default()
+        }),
+      }
+    }
+    None => fut.await,
+  }
+}
+
+/// 非进程类步骤的输出, 返回码 0 成功 1 失败
+fn output(ok: bool, stdout: impl Into<String>, stderr: impl Into<String>, start: Instant) -> StepOutput {
+  StepOutput {
+    code: Some(if ok { 0 } else { 1 }),
+    stdout: stdout.into(),
+    stderr: stderr.into(),
+    timeout: false,
+    elapsed: start.elapsed(),
+  }
+}
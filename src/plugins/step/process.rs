@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::StepRunner;
+use crate::{
+  db::r#type::app::ExtendApp,
+  plugins::executor::{execute, LineTx, StepOutput},
+  res::Result,
+};
+
+/// 外部进程, 在`cwd`中执行`cmd`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessStep {}
+
+impl StepRunner for ProcessStep {
+  async fn run(&self, app: &ExtendApp, lines: Option<LineTx>) -> Result<StepOutput> {
+    execute(app, lines).await
+  }
+}
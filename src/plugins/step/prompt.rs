@@ -0,0 +1,97 @@
+use std::sync::{
+  atomic::{AtomicU64, Ordering},
+  Mutex,
+};
+
+use e_utils::once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::{sync::oneshot, time::Instant};
+
+use super::{output, StepRunner};
+use crate::{
+  db::r#type::app::ExtendApp,
+  plugins::executor::{LineTx, StepOutput},
+  res::{Error, PanicAny as _, Result},
+};
+
+/// 回答方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PromptInput {
+  /// 通过/不通过, 结果值为`true`/`false`
+  #[default]
+  Confirm,
+  /// 文本, 结果值为输入内容
+  Text,
+  /// 数字, 结果值为输入内容
+  Number,
+}
+
+/// 操作员提示, 暂停当前步骤并在主窗口显示模态框, 如目检"LED是否为绿色"
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PromptStep {
+  pub message: String,
+  /// 图片, `IMG_MAP`中的键或本地路径(相对路径基于程序目录)
+  pub image: Option<String>,
+  pub input: PromptInput,
+}
+
+/// 等待回答的提示
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prompt {
+  pub id: u64,
+  pub title: String,
+  pub message: String,
+  pub image: Option<String>,
+  pub input: PromptInput,
+}
+
+/// 操作员的回答
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+  Pass,
+  Fail,
+  Value(String),
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+/// 等待回答的提示, 按提出顺序显示
+static PENDING: Lazy<Mutex<Vec<(Prompt, oneshot::Sender<Answer>)>>> = Lazy::new(|| Mutex::new(vec![]));
+
+/// 当前需要显示的提示, 已中止或超时的提示会被移除
+pub fn pending() -> Option<Prompt> {
+  let mut pending = PENDING.lock().panic("prompt pending");
+  pending.retain(|(_, tx)| !tx.is_closed());
+  pending.first().map(|(x, _)| x.clone())
+}
+
+/// 回答提示
+pub fn answer(id: u64, answer: Answer) {
+  let mut pending = PENDING.lock().panic("prompt answer");
+  if let Some(i) = pending.iter().position(|(x, _)| x.id == id) {
+    let (_, tx) = pending.remove(i);
+    let _ = tx.send(answer);
+  }
+}
+
+impl StepRunner for PromptStep {
+  async fn run(&self, app: &ExtendApp, _lines: Option<LineTx>) -> Result<StepOutput> {
+    let start = Instant::now();
+    let (tx, rx) = oneshot::channel();
+    let prompt = Prompt {
+      id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+      title: app.label.clone(),
+      message: self.message.clone(),
+      image: self.image.clone(),
+      input: self.input,
+    };
+    PENDING.lock().panic("prompt push").push((prompt, tx));
+    // 中止或超时时`rx`被丢弃, 提示随之失效
+    Ok(match rx.await.map_err(|_| Error::Empty)? {
+      Answer::Pass => output(true, "true", "", start),
+      Answer::Fail => output(false, "false", "", start),
+      Answer::Value(v) => output(true, v, "", start),
+    })
+  }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use super::StepRunner;
+use crate::{
+  db::r#type::app::ExtendApp,
+  plugins::executor::{execute, LineTx, StepOutput},
+  res::Result,
+};
+
+/// 脚本解释器
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Shell {
+  /// Windows 使用`cmd`, 其他使用`sh`
+  #[default]
+  Auto,
+  Sh,
+  Bash,
+  Cmd,
+  PowerShell,
+}
+impl Shell {
+  /// 解释器及执行脚本的参数
+  fn program(&self) -> [&'static str; 2] {
+    match self {
+      Shell::Auto if cfg!(windows) => ["cmd", "/C"],
+      Shell::Auto | Shell::Sh => ["sh", "-c"],
+      Shell::Bash => ["bash", "-c"],
+      Shell::Cmd => ["cmd", "/C"],
+      Shell::PowerShell => ["powershell", "-Command"],
+    }
+  }
+}
+
+/// 脚本, 在`cwd`中由`shell`执行
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ShellStep {
+  pub shell: Shell,
+  pub script: String,
+}
+
+impl StepRunner for ShellStep {
+  async fn run(&self, app: &ExtendApp, lines: Option<LineTx>) -> Result<StepOutput> {
+    let [program, arg] = self.shell.program();
+    let app = ExtendApp {
+      cmd: vec![program.to_string(), arg.to_string(), self.script.clone()],
+      ..app.clone()
+    };
+    execute(&app, lines).await
+  }
+}
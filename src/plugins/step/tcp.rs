@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use tokio::{net::TcpStream, time::Instant};
+
+use super::{output, StepRunner};
+use crate::{
+  db::r#type::app::ExtendApp,
+  plugins::executor::{LineTx, StepOutput},
+  res::Result,
+};
+
+/// TCP 端口探测, 能建立连接视为成功, 输出连接耗时(毫秒)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TcpStep {
+  pub host: String,
+  pub port: u16,
+}
+
+impl StepRunner for TcpStep {
+  async fn run(&self, _app: &ExtendApp, _lines: Option<LineTx>) -> Result<StepOutput> {
+    let start = Instant::now();
+    Ok(match TcpStream::connect((self.host.as_str(), self.port)).await {
+      Ok(_) => output(true, start.elapsed().as_millis().to_string(), "", start),
+      Err(e) => output(false, "", format!("{}:{} {e}", self.host, self.port), start),
+    })
+  }
+}
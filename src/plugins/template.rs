@@ -0,0 +1,94 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  sync::{Arc, RwLock},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{
+  config::Config,
+  db::r#type::app::ExtendApp,
+  res::{Error, PanicAny as _, Result},
+};
+
+/// 步骤模板变量, 每次运行新建
+///
+/// 除`Config::rkey`的内置键外, 支持运行变量`#sn#`、`#station#`、`#operator#`、`#run_id#`、`#date#`,
+/// 环境变量`#env:VAR#`, 已完成步骤的结果值`#step:TAG#`, 以及步骤通过`capture`保存的变量`#name#`
+///
+/// 未知的变量返回错误, 字面的`#`写作`##`
+#[derive(Debug, Clone, Default)]
+pub struct Template {
+  cfg: Config,
+  vars: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl Template {
+  pub fn new(cfg: Config, vars: HashMap<String, String>) -> Self {
+    Self {
+      cfg,
+      vars: Arc::new(RwLock::new(vars)),
+    }
+  }
+
+  /// 全局配置
+  pub fn config(&self) -> &Config {
+    &self.cfg
+  }
+
+  /// 设置变量
+  pub fn set(&self, key: impl Into<String>, value: impl Into<String>) {
+    self.vars.write().panic("template set").insert(key.into(), value.into());
+  }
+
+  /// 当前变量, 按名称排序
+  pub fn vars(&self) -> BTreeMap<String, String> {
+    let vars = self.vars.read().panic("template vars");
+    vars.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+  }
+
+  /// 保存到测试记录
+  pub fn to_value(&self) -> Value {
+    serde_json::to_value(self.vars()).unwrap_or_default()
+  }
+
+  /// 替换`#key#`
+  pub fn render(&self, value: &str) -> Result<String> {
+    self.cfg.rkey_with(value, &self.vars.read().panic("template render"))
+  }
+
+  /// 替换JSON中所有字符串的变量
+  pub fn render_value(&self, value: Value) -> Result<Value> {
+    Ok(match value {
+      Value::String(s) if s.contains('#') => Value::String(self.render(&s)?),
+      Value::Array(arr) => Value::Array(arr.into_iter().map(|x| self.render_value(x)).collect::<Result<_>>()?),
+      Value::Object(obj) => Value::Object(
+        obj
+          .into_iter()
+          .map(|(k, v)| Ok((k, self.render_value(v)?)))
+          .collect::<Result<_>>()?,
+      ),
+      v => v,
+    })
+  }
+
+  /// 替换结构中所有字符串的变量, 如限值`{"type": "exact", "value": "#mac#"}`
+  pub fn render_json<T: Serialize + DeserializeOwned>(&self, v: &T) -> Result<T> {
+    let value = serde_json::to_value(v).map_err(|e| Error::DataParse(e.to_string()))?;
+    serde_json::from_value(self.render_value(value)?).map_err(|e| Error::DataParse(e.to_string()))
+  }
+
+  /// 替换步骤的`cmd`、`cwd`、`res_url`、`limits`及类型参数
+  pub fn apply(&self, app: &ExtendApp) -> Result<ExtendApp> {
+    let render = |x: &Option<String>| x.as_deref().map(|x| self.render(x)).transpose();
+    Ok(ExtendApp {
+      cmd: app.cmd.iter().map(|x| self.render(x)).collect::<Result<_>>()?,
+      cwd: render(&app.cwd)?,
+      res_url: render(&app.res_url)?,
+      limits: self.render_json(&app.limits)?,
+      r#type: self.render_json(&app.r#type)?,
+      ..app.clone()
+    })
+  }
+}
@@ -0,0 +1,164 @@
+//! 结果上传, 先写入`outbox`再由后台按顺序发送, MES 不可用时不丢失结果
+//!
+//! 发送失败按指数退避重试, 间隔最长`MAX_BACKOFF`, 网络错误和5xx一直重试;
+//! 被拒绝(4xx)的不再发送, 不阻塞后续结果, 可由`requeue`重新加入队列
+use std::time::Duration;
+
+use e_utils::{
+  once_cell::sync::Lazy,
+  time::{DateTime, Utc},
+};
+use reqwest::{
+  header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE},
+  StatusCode,
+};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use tokio::sync::Mutex;
+
+use super::{limit::as_text, sequence::StepResult};
+use crate::{
+  config::{
+    logger::{log, Tag},
+    mes::MesCfg,
+  },
+  db::{
+    apis::outbox::{
+      count_dead_outbox, count_outbox, delete_outbox, fail_outbox, insert_outbox, requeue_outbox, select_outbox,
+    },
+    r#type::{app::ExtendApp, run::TestRun},
+  },
+  res::{Error, Result},
+};
+
+/// 每次发送的最大数量
+const BATCH: i64 = 100;
+/// 最长重试间隔(秒)
+const MAX_BACKOFF: i64 = 3600;
+/// 待上传数量超过时警告
+const BACKLOG: i64 = 1000;
+/// 同一时间只有一个发送任务, 保证顺序且不重复发送
+static FLUSH: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 步骤结果
+pub fn step_payload(run: &TestRun, app: &ExtendApp, res: &StepResult) -> Value {
+  json!({
+    "type": "step",
+    "runId": run.id,
+    "sn": run.sn,
+    "station": run.station,
+    "tag": app.tag,
+    "label": app.label,
+    "state": res.state.as_str(),
+    "value": res.res_value,
+    "text": as_text(&res.res_value),
+    "limits": app.limits,
+    "violation": res.violation.as_ref().map(|x| x.to_string()),
+    "attempts": res.attempts.len(),
+  })
+}
+
+/// 测试结果, 含全部步骤结果
+pub fn run_payload(run: &TestRun, steps: Vec<Value>) -> Value {
+  let mut payload = serde_json::to_value(run).unwrap_or_default();
+  if let Value::Object(map) = &mut payload {
+    map.insert("type".to_string(), json!("run"));
+    map.insert("steps".to_string(), Value::Array(steps));
+  }
+  payload
+}
+
+/// 加入待上传队列
+pub async fn enqueue(pool: &SqlitePool, url: &str, payload: &Value) {
+  if let Err(e) = insert_outbox(pool, url, payload).await {
+    log::error(format!("保存待上传结果失败 {url}: {e}"), Tag::AutoTest);
+  }
+}
+
+/// 发送一次
+async fn post(client: &reqwest::Client, cfg: &MesCfg, url: &str, payload: &Value) -> Result<()> {
+  let mut headers = HeaderMap::new();
+  headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+  if let Some(map) = cfg.headers.as_object() {
+    for (k, v) in map {
+      let name = HeaderName::try_from(k.as_str()).map_err(|e| Error::DataParse(format!("请求头 {k}: {e}")))?;
+      let value =
+        HeaderValue::try_from(as_text(v)).map_err(|e| Error::DataParse(format!("请求头 {k}: {e}")))?;
+      headers.insert(name, value);
+    }
+  }
+  if !cfg.token.is_empty() {
+    let value = HeaderValue::try_from(format!("Bearer {}", cfg.token))
+      .map_err(|e| Error::DataParse(format!("Token: {e}")))?;
+    headers.insert(AUTHORIZATION, value);
+  }
+  client
+    .post(url)
+    .headers(headers)
+    .timeout(Duration::from_secs(cfg.timeout.max(1) as u64))
+    .body(payload.to_string())
+    .send()
+    .await?
+    .error_for_status()?;
+  Ok(())
+}
+
+/// 服务端拒绝, 重试也不会成功; 超时(408)和限流(429)除外
+fn rejected(e: &Error) -> bool {
+  match e {
+    Error::Http(e) => e.status().is_some_and(|status| {
+      status.is_client_error() && !matches!(status, StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS)
+    }),
+    _ => false,
+  }
+}
+
+/// 第`attempts`次失败后的重试间隔(秒): `retry_interval`起每次翻倍, 最长`MAX_BACKOFF`
+fn backoff(cfg: &MesCfg, attempts: i32) -> i64 {
+  let base = cfg.retry_interval.max(5) as i64;
+  base.saturating_mul(1 << attempts.clamp(0, 20)).min(MAX_BACKOFF)
+}
+
+/// 已放弃上传的结果重新加入队列, 如 MES 修正后; 返回重新加入的数量
+pub async fn requeue(pool: &SqlitePool) -> Result<i64> {
+  let _guard = FLUSH.lock().await;
+  let n = requeue_outbox(pool).await?;
+  log::info(format!("重新上传已放弃的结果: {n}"), Tag::AutoTest);
+  Ok(n)
+}
+
+/// 按加入顺序发送已到重试时间的结果, 失败的推迟重试并继续发送后续结果; 返回`(待上传, 已放弃)`数量
+pub async fn flush(pool: &SqlitePool, cfg: &MesCfg) -> Result<(i64, i64)> {
+  let _guard = FLUSH.lock().await;
+  let client = reqwest::Client::new();
+  loop {
+    let items = select_outbox(pool, Utc::now(), BATCH).await?;
+    if items.is_empty() {
+      break;
+    }
+    for item in &items {
+      match post(&client, cfg, &item.url, &item.payload).await {
+        Ok(()) => {
+          delete_outbox(pool, item.id).await?;
+        }
+        Err(e) => {
+          let attempts = item.attempts + 1;
+          let dead = rejected(&e);
+          let e = e.to_string();
+          let next_at = Utc::now().timestamp() + backoff(cfg, item.attempts);
+          let next_at = DateTime::<Utc>::from_timestamp(next_at, 0).unwrap_or_else(Utc::now);
+          match dead {
+            true => log::error(format!("放弃上传(第{attempts}次) {}: {e}", item.url), Tag::AutoTest),
+            false => log::warn(format!("上传失败(第{attempts}次) {}: {e}", item.url), Tag::AutoTest),
+          }
+          fail_outbox(pool, item.id, &e, next_at, dead).await?;
+        }
+      }
+    }
+  }
+  let pending = count_outbox(pool).await?;
+  if pending > BACKLOG {
+    log::warn(format!("待上传结果积压: {pending}"), Tag::AutoTest);
+  }
+  Ok((pending, count_dead_outbox(pool).await?))
+}
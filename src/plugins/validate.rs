@@ -0,0 +1,232 @@
+use std::{
+  collections::HashSet,
+  fmt::{self, Display},
+  path::Path,
+};
+
+use super::{executor::work_dir, filter::Filter, schedule::schedule, step::StepKind, template::Template};
+use crate::db::r#type::app::ExtendApp;
+
+/// 问题级别, `Error`阻止运行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+  Error,
+  Warning,
+  Info,
+}
+impl Display for Level {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Level::Error => write!(f, "错误"),
+      Level::Warning => write!(f, "警告"),
+      Level::Info => write!(f, "信息"),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+  pub level: Level,
+  /// 步骤`tag`, 序列级问题为空
+  pub tag: String,
+  pub message: String,
+}
+impl Display for Issue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.tag.is_empty() {
+      true => write!(f, "[{}] {}", self.level, self.message),
+      false => write!(f, "[{}][{}] {}", self.level, self.tag, self.message),
+    }
+  }
+}
+
+/// 检查结果
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+  pub issues: Vec<Issue>,
+}
+impl Report {
+  pub fn push(&mut self, level: Level, tag: impl Into<String>, message: impl Into<String>) {
+    self.issues.push(Issue {
+      level,
+      tag: tag.into(),
+      message: message.into(),
+    });
+  }
+
+  pub fn has_errors(&self) -> bool {
+    self.issues.iter().any(|x| x.level == Level::Error)
+  }
+
+  /// 序列级错误, 如加载失败
+  pub fn error(message: impl Into<String>) -> Self {
+    let mut slf = Self::default();
+    slf.push(Level::Error, "", message);
+    slf
+  }
+}
+
+/// 含`#key#`的值运行时才能确定, 不检查
+fn is_template(v: &str) -> bool {
+  v.contains('#')
+}
+
+/// 查找可执行文件: 含目录时按路径(相对步骤的工作目录), 否则在`PATH`中查找
+fn find_program(program: &str, app: &ExtendApp) -> bool {
+  let path = Path::new(program);
+  if path.components().count() > 1 {
+    return work_dir(app).join(path).is_file();
+  }
+  let exts: Vec<String> = match cfg!(windows) {
+    true => std::iter::once(String::new())
+      .chain(
+        std::env::var("PATHEXT")
+          .unwrap_or(".COM;.EXE;.BAT;.CMD".to_string())
+          .split(';')
+          .map(String::from),
+      )
+      .collect(),
+    false => vec![String::new()],
+  };
+  std::env::var_os("PATH")
+    .map(|paths| {
+      std::env::split_paths(&paths).any(|dir| exts.iter().any(|ext| dir.join(format!("{program}{ext}")).is_file()))
+    })
+    .unwrap_or(false)
+}
+
+/// 检查步骤类型的参数, 含变量的参数不检查
+///
+/// 只检查格式, 不访问网络和设备: 不检查 HTTP 地址、TCP 端口能否连通,
+/// 也不检查文件步骤的文件是否存在(由步骤本身判定)及提示的图片
+fn check_kind(app: &ExtendApp, report: &mut Report) {
+  let ref tag = app.tag;
+  let mut error = |message: String| report.push(Level::Error, tag, message);
+  match &app.r#type {
+    StepKind::Process(_) => match app.cmd.first() {
+      None => error("命令为空".to_string()),
+      Some(program) if !is_template(program) && !find_program(program, app) => {
+        error(format!("找不到可执行文件: {program}"))
+      }
+      Some(_) => {}
+    },
+    StepKind::Shell(x) if x.script.trim().is_empty() => error("脚本为空".to_string()),
+    StepKind::Prompt(x) if x.message.trim().is_empty() => error("提示内容为空".to_string()),
+    StepKind::Http(x) => {
+      if !is_template(&x.method) && reqwest::Method::from_bytes(x.method.to_uppercase().as_bytes()).is_err() {
+        error(format!("不支持的请求方法: {}", x.method));
+      }
+      if !is_template(&x.url) {
+        match reqwest::Url::parse(&x.url) {
+          Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+          _ => error(format!("请求地址无效: {}", x.url)),
+        }
+      }
+    }
+    StepKind::File(x) => {
+      if x.path.is_empty() {
+        error("文件路径为空".to_string());
+      }
+      let sha256 = x.sha256.as_deref().map(str::trim).filter(|x| !is_template(x));
+      if let Some(sha256) = sha256.filter(|x| x.len() != 64 || !x.chars().all(|c| c.is_ascii_hexdigit())) {
+        error(format!("SHA-256 无效: {sha256}"));
+      }
+    }
+    StepKind::Tcp(x) => {
+      if x.host.trim().is_empty() {
+        error("主机为空".to_string());
+      }
+      if x.port == 0 {
+        error("端口无效: 0".to_string());
+      }
+    }
+    StepKind::Delay(x) if x.delay_ms == 0 => report.push(Level::Warning, tag, "延时为0"),
+    _ => {}
+  }
+}
+
+/// 运行前检查已启用的步骤
+///
+/// 标签唯一、依赖存在、执行条件有效; 工作目录存在, 步骤类型的参数有效(见`check_kind`);
+/// `filter`和限值有效; 超时为正
+pub fn validate(apps: &[ExtendApp]) -> Report {
+  let mut report = Report::default();
+  let mut tags = HashSet::new();
+  for app in apps {
+    if !tags.insert(app.tag.as_str()) {
+      report.push(Level::Error, &app.tag, "步骤标签重复");
+    }
+  }
+  for app in apps.iter().filter(|x| x.enable) {
+    let ref tag = app.tag;
+    for dep in app.depends_on.iter().filter(|x| !tags.contains(x.as_str())) {
+      report.push(Level::Error, tag, format!("依赖不存在: {dep}"));
+    }
+    match app.timeout {
+      t if t < 0 => report.push(Level::Error, tag, format!("超时时间无效: {t}")),
+      0 => report.push(Level::Warning, tag, "未设置超时, 将一直等待"),
+      _ => {}
+    }
+    if app.is_repeat && app.count < 1 {
+      report.push(Level::Error, tag, format!("重复次数无效: {}", app.count));
+    }
+    let cwd = app.cwd.as_deref().filter(|x| !x.is_empty() && !is_template(x));
+    if let Some(cwd) = cwd.filter(|_| !work_dir(app).is_dir()) {
+      report.push(Level::Error, tag, format!("工作目录不存在: {cwd}"));
+    }
+    check_kind(app, &mut report);
+    for rule in &app.filter {
+      if let Err(e) = rule.parse::<Filter>() {
+        report.push(Level::Error, tag, format!("结果提取规则无效: {e}"));
+      }
+    }
+    if let Err(e) = app.limits.validate() {
+      report.push(Level::Error, tag, format!("限值无效: {e}"));
+    }
+  }
+  // 循环依赖、执行条件
+  if !report.has_errors() {
+    if let Err(e) = schedule(apps.to_vec()) {
+      report.push(Level::Error, "", e.to_string());
+    }
+  }
+  report
+}
+
+/// 命令行显示, 含空白的参数加引号
+fn command_line(cmd: &[String]) -> String {
+  let quote = |x: &String| match x.contains(char::is_whitespace) {
+    true => format!("\"{x}\""),
+    false => x.clone(),
+  };
+  cmd.iter().map(quote).collect::<Vec<_>>().join(" ")
+}
+
+/// 预演: 按执行顺序列出替换变量后的命令, 不执行; `apps`为排序后的步骤
+pub fn dry_run(apps: &[ExtendApp], template: &Template) -> Report {
+  let mut report = Report::default();
+  for (i, app) in apps.iter().enumerate() {
+    // 引用前序步骤结果的变量运行时才能替换
+    let (app, note) = match template.apply(app) {
+      Ok(app) => (app, String::new()),
+      Err(e) => (app.clone(), format!(" ({e}, 运行时替换)")),
+    };
+    let action = match &app.r#type {
+      StepKind::Process(_) => command_line(&app.cmd),
+      kind => format!("{kind} {}", serde_json::to_string(kind).unwrap_or_default()),
+    };
+    let mut line = format!("{}. {action}", i + 1);
+    if let Some(cwd) = app.cwd.as_ref().filter(|x| !x.is_empty()) {
+      line.push_str(&format!(" @ {cwd}"));
+    }
+    if !app.depends_on.is_empty() {
+      line.push_str(&format!(" 依赖: {}", app.depends_on.join(", ")));
+    }
+    if app.is_wait {
+      line.push_str(" 单独执行");
+    }
+    line.push_str(&note);
+    report.push(Level::Info, &app.tag, line);
+  }
+  report
+}
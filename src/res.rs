@@ -15,6 +15,12 @@ pub enum Error {
   /// 数据库
   #[error("Database Sqlx: {0}")]
   Database(#[from] sqlx::Error),
+  /// IO
+  #[error("IO: {0}")]
+  Io(#[from] std::io::Error),
+  /// HTTP
+  #[error("HTTP: {0}")]
+  Http(#[from] reqwest::Error),
   /// 数据解析
   #[error("Data parse: {0}")]
   DataParse(String),
@@ -55,6 +61,8 @@ impl Error {
     match self {
       Self::BadRequest
       | Self::DataParse(_)
+      | Self::Io(_)
+      | Self::Http(_)
       | Self::Log(_)
       | Self::Exists(_)
       | Self::Empty
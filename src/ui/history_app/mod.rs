@@ -0,0 +1,525 @@
+//! 测试记录查询窗口
+use std::path::PathBuf;
+
+use e_utils::time::{DateTime, Utc};
+use iced::{
+  widget::{button, column, container, horizontal_space, pick_list, row, scrollable, text, text_input},
+  window, Alignment, Command, Length, Size,
+};
+
+mod table;
+use super::main_app::{autotest::DataState, AppWindow};
+use crate::{
+  config::{
+    logger::{log, Tag},
+    theme, Config,
+  },
+  data::{icon, DB_SQLITE},
+  db::{
+    apis::{
+      run::{count_runs, select_run, select_runs, select_step_results},
+      user::select_all_users,
+    },
+    r#type::{
+      run::{RunQuery, TestRun, TestStepResult},
+      user::User,
+    },
+  },
+  plugins::report,
+  ui::components::{helpers::text_with_logo, Column, Element},
+};
+
+/// 每页数量
+const PAGE_SIZE: i64 = 50;
+/// 不过滤
+const ALL: &str = "全部";
+/// 可筛选的结果
+const RESULTS: [&str; 5] = [ALL, "Pass", "Fail", "Abort", "Running"];
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  SetSn(String),
+  SetStart(String),
+  SetEnd(String),
+  SetStation(String),
+  SetOperator(String),
+  SetResult(String),
+  SetStep(String),
+  /// 按条件从第一页查询
+  Search,
+  Loaded(Result<(Vec<TestRun>, i64), String>),
+  UsersLoaded(Result<Vec<User>, String>),
+  PrevPage,
+  NextPage,
+  /// 查看测试记录的步骤
+  Open(i64),
+  Opened(Result<(TestRun, Vec<TestStepResult>), String>),
+  /// 返回列表
+  Back,
+  /// 按当前报告配置重新生成报告
+  Export,
+  Exported(Result<Vec<PathBuf>, String>),
+  Steps(table::Message),
+  /// 查看步骤在工站的SPC控制图: `(tag, 工站)`
+  Spc(String, String),
+}
+
+/// 查询条件输入
+#[derive(Debug, Clone)]
+pub struct Filter {
+  pub sn: String,
+  /// `YYYY-MM-DD`(UTC)或 RFC3339 时间
+  pub start: String,
+  pub end: String,
+  pub station: String,
+  /// 操作员名称
+  pub operator: String,
+  pub result: String,
+  /// 最终失败的步骤`tag`
+  pub step: String,
+}
+impl Default for Filter {
+  fn default() -> Self {
+    Self {
+      sn: String::new(),
+      start: String::new(),
+      end: String::new(),
+      station: ALL.to_string(),
+      operator: ALL.to_string(),
+      result: ALL.to_string(),
+      step: String::new(),
+    }
+  }
+}
+
+/// 解析时间, 仅日期时`end`取当天结束
+pub fn parse_time(value: &str, end: bool) -> Result<Option<DateTime<Utc>>, String> {
+  let value = value.trim();
+  if value.is_empty() {
+    return Ok(None);
+  }
+  let full = match value.len() {
+    10 => format!("{value}T{}Z", if end { "23:59:59.999999999" } else { "00:00:00" }),
+    _ => value.replacen(' ', "T", 1),
+  };
+  full
+    .parse::<DateTime<Utc>>()
+    .map(Some)
+    .map_err(|e| format!("时间格式无效 {value}: {e}"))
+}
+
+/// 测试记录查询
+pub struct App {
+  pub id: window::Id,
+  pub flag: Config,
+  pub filter: Filter,
+  pub users: Vec<User>,
+  pub runs: Vec<TestRun>,
+  pub total: i64,
+  pub page: i64,
+  /// 查看中的测试记录
+  pub run: Option<TestRun>,
+  steps: table::Steps,
+  pub loading: bool,
+  /// 查询或导出的提示
+  pub notice: Option<Result<String, String>>,
+}
+
+impl Default for App {
+  fn default() -> Self {
+    Self {
+      id: window::Id::MAIN,
+      flag: Config::default(),
+      filter: Filter::default(),
+      users: vec![],
+      runs: vec![],
+      total: 0,
+      page: 0,
+      run: None,
+      steps: table::Steps::default(),
+      loading: false,
+      notice: None,
+    }
+  }
+}
+
+impl App {
+  /// 当前查询条件
+  fn query(&self) -> Result<RunQuery, String> {
+    let ref f = self.filter;
+    let some = |v: &str| (v != ALL).then(|| v.to_string());
+    let user_id = match some(&f.operator) {
+      Some(name) => Some(
+        self
+          .users
+          .iter()
+          .find(|x| x.name == name)
+          .map(|x| x.id)
+          .ok_or(format!("未找到操作员: {name}"))?,
+      ),
+      None => None,
+    };
+    Ok(RunQuery {
+      sn: Some(f.sn.trim().to_string()),
+      station: some(&f.station),
+      user_id,
+      result: some(&f.result),
+      start: parse_time(&f.start, false)?,
+      end: parse_time(&f.end, true)?,
+      failed_step: Some(f.step.trim().to_string()),
+      limit: PAGE_SIZE,
+      offset: self.page * PAGE_SIZE,
+    })
+  }
+
+  fn pages(&self) -> i64 {
+    ((self.total + PAGE_SIZE - 1) / PAGE_SIZE).max(1)
+  }
+
+  /// 查询当前页
+  fn search(&mut self) -> Command<Message> {
+    let q = match self.query() {
+      Ok(q) => q,
+      Err(e) => {
+        self.notice = Some(Err(e));
+        return Command::none();
+      }
+    };
+    self.loading = true;
+    Command::perform(
+      async move {
+        let pool = DB_SQLITE.read().await.pool();
+        let runs = select_runs(&pool, &q).await.map_err(|e| e.to_string())?;
+        let total = count_runs(&pool, &q).await.map_err(|e| e.to_string())?;
+        Ok((runs, total))
+      },
+      Message::Loaded,
+    )
+  }
+
+  /// 按条件显示列表, 如统计中选中失败步骤; 窗口未打开时打开
+  pub fn filter_by(&mut self, filter: Filter) -> Command<Message> {
+    self.filter = filter;
+    self.page = 0;
+    self.run = None;
+    self.notice = None;
+    match self.exist() {
+      true => Command::batch([self.search(), self.focus()]),
+      false => self.create(),
+    }
+  }
+
+  /// 操作员名称
+  fn operator(&self, user_id: i32) -> String {
+    match self.users.iter().find(|x| x.id == user_id) {
+      Some(user) => user.name.clone(),
+      None => user_id.to_string(),
+    }
+  }
+
+  fn filter_view(&self) -> Element<'_, Message> {
+    let ref f = self.filter;
+    let stations: Vec<String> = std::iter::once(ALL.to_string())
+      .chain(self.flag.stations.iter().map(|x| x.name.clone()))
+      .collect();
+    let operators: Vec<String> = std::iter::once(ALL.to_string())
+      .chain(self.users.iter().map(|x| x.name.clone()))
+      .collect();
+    let results: Vec<String> = RESULTS.iter().map(|x| x.to_string()).collect();
+    column![
+      row![
+        "SN：",
+        text_input("模糊匹配", &f.sn)
+          .width(240)
+          .on_input(Message::SetSn)
+          .on_submit(Message::Search),
+        "时间：",
+        text_input("2024-01-01", &f.start)
+          .width(200)
+          .on_input(Message::SetStart)
+          .on_submit(Message::Search),
+        "至",
+        text_input("2024-01-31", &f.end)
+          .width(200)
+          .on_input(Message::SetEnd)
+          .on_submit(Message::Search),
+      ]
+      .spacing(8)
+      .align_items(Alignment::Center),
+      row![
+        "工站：",
+        pick_list(stations, Some(f.station.clone()), Message::SetStation),
+        "操作员：",
+        pick_list(operators, Some(f.operator.clone()), Message::SetOperator),
+        "结果：",
+        pick_list(results, Some(f.result.clone()), Message::SetResult),
+        "失败步骤：",
+        text_input("tag", &f.step)
+          .width(140)
+          .on_input(Message::SetStep)
+          .on_submit(Message::Search),
+        horizontal_space(),
+        button(text_with_logo(icon::filter(), "查询"))
+          .on_press_maybe((!self.loading).then_some(Message::Search)),
+      ]
+      .spacing(8)
+      .align_items(Alignment::Center),
+    ]
+    .spacing(8)
+    .into()
+  }
+
+  fn notice_view(&self) -> Element<'_, Message> {
+    match &self.notice {
+      Some(Ok(msg)) => text(msg).style(theme::Text::Success).into(),
+      Some(Err(msg)) => text(msg).style(theme::Text::Error).into(),
+      None => horizontal_space().into(),
+    }
+  }
+
+  /// 测试记录列表
+  fn runs_view(&self) -> Element<'_, Message> {
+    let cell = |content: String, width: f32| container(text(content)).width(width);
+    let header = row![
+      cell("ID".to_string(), 70.0),
+      cell("SN".to_string(), 260.0),
+      cell("工站".to_string(), 100.0),
+      cell("操作员".to_string(), 100.0),
+      cell("结果".to_string(), 80.0),
+      cell("开始时间".to_string(), 180.0),
+      cell("耗时".to_string(), 80.0),
+    ]
+    .spacing(8);
+    let rows = Column::with_children(self.runs.iter().map(|run| {
+      let elapsed = run
+        .ended_at
+        .map(|x| format!("{:.1}s", (x - run.started_at).num_milliseconds() as f64 / 1000.0))
+        .unwrap_or_default();
+      button(
+        row![
+          cell(run.id.to_string(), 70.0),
+          cell(run.sn.clone(), 260.0),
+          cell(run.station.clone(), 100.0),
+          cell(self.operator(run.user_id), 100.0),
+          container(DataState::from(run.result.as_str()).to_text(15)).width(80),
+          cell(run.started_at.format("%Y-%m-%d %H:%M:%S").to_string(), 180.0),
+          cell(elapsed, 80.0),
+        ]
+        .spacing(8),
+      )
+      .width(Length::Fill)
+      .style(theme::Button::Ready)
+      .on_press(Message::Open(run.id))
+      .into()
+    }))
+    .spacing(2);
+    let pager = row![
+      text(format!("共{}条 第{}/{}页", self.total, self.page + 1, self.pages())),
+      horizontal_space(),
+      button("上一页").on_press_maybe((self.page > 0).then_some(Message::PrevPage)),
+      button("下一页").on_press_maybe((self.page + 1 < self.pages()).then_some(Message::NextPage)),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+    column![
+      header,
+      scrollable(rows).height(Length::Fill).style(theme::Scrollable::Normal),
+      pager
+    ]
+    .spacing(6)
+    .into()
+  }
+
+  /// 测试记录详情
+  fn run_view<'a>(&'a self, run: &'a TestRun) -> Element<'a, Message> {
+    let info = row![
+      button(text_with_logo(icon::undo(), "返回")).on_press(Message::Back),
+      text(format!("#{} {} {} {}", run.id, run.sn, run.station, self.operator(run.user_id))),
+      DataState::from(run.result.as_str()).to_text(18),
+      text(run.started_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+      horizontal_space(),
+      button(text_with_logo(icon::layout(), "SPC"))
+        .on_press_maybe(self.steps.spc_tag().map(|tag| Message::Spc(tag, run.station.clone()))),
+      button(text_with_logo(icon::export(), "导出报告"))
+        .on_press_maybe((!self.loading && self.flag.report.enabled()).then_some(Message::Export)),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+    column![info, self.steps.view().map(Message::Steps)].spacing(6).into()
+  }
+}
+
+impl AppWindow for App {
+  type Event = Message;
+  type Flag = Config;
+  type Setting = Config;
+
+  fn new(flag: Self::Flag) -> Self {
+    let mut slf = Self::default();
+    slf.flag = flag;
+    slf
+  }
+
+  fn settings(flag: Self::Setting) -> window::Settings {
+    let ref cfg = flag.user_cfg;
+    let size = Size::new(cfg.width, cfg.height);
+    window::Settings {
+      icon: super::main_app::application_icon(),
+      size,
+      min_size: Some(size),
+      position: window::Position::Default,
+      resizable: cfg.resizable,
+      decorations: cfg.decorations,
+      transparent: cfg.transparent,
+      exit_on_close_request: true,
+      ..Default::default()
+    }
+  }
+
+  fn title(&self) -> String {
+    format!("测试记录")
+  }
+
+  fn create(&mut self) -> Command<Self::Event> {
+    if self.exist() {
+      return Command::batch([self.show(), self.focus()]);
+    }
+    let (id, spawn_window) = window::spawn(Self::settings(self.flag.clone()));
+    self.id = id;
+    let users = Command::perform(
+      async move {
+        let pool = DB_SQLITE.read().await.pool();
+        select_all_users(&pool).await.map_err(|e| e.to_string())
+      },
+      Message::UsersLoaded,
+    );
+    Command::batch([spawn_window, users, self.search()])
+  }
+
+  fn update(&mut self, event: Self::Event) -> Command<Self::Event> {
+    match event {
+      Message::SetSn(v) => self.filter.sn = v,
+      Message::SetStart(v) => self.filter.start = v,
+      Message::SetEnd(v) => self.filter.end = v,
+      Message::SetStation(v) => self.filter.station = v,
+      Message::SetOperator(v) => self.filter.operator = v,
+      Message::SetResult(v) => self.filter.result = v,
+      Message::SetStep(v) => self.filter.step = v,
+      Message::Search => {
+        self.page = 0;
+        self.notice = None;
+        return self.search();
+      }
+      Message::Loaded(res) => {
+        self.loading = false;
+        match res {
+          Ok((runs, total)) => {
+            self.runs = runs;
+            self.total = total;
+          }
+          Err(e) => {
+            log::error(format!("查询测试记录失败: {e}"), Tag::DatabaseOffline);
+            self.notice = Some(Err(format!("查询失败: {e}")));
+          }
+        }
+      }
+      Message::UsersLoaded(res) => match res {
+        Ok(users) => self.users = users,
+        Err(e) => log::error(format!("加载用户失败: {e}"), Tag::DatabaseOffline),
+      },
+      Message::PrevPage => {
+        self.page = (self.page - 1).max(0);
+        return self.search();
+      }
+      Message::NextPage => {
+        self.page = (self.page + 1).min(self.pages() - 1);
+        return self.search();
+      }
+      Message::Open(id) => {
+        self.loading = true;
+        return Command::perform(
+          async move {
+            let pool = DB_SQLITE.read().await.pool();
+            let run = select_run(&pool, id).await.map_err(|e| e.to_string())?;
+            let steps = select_step_results(&pool, id).await.map_err(|e| e.to_string())?;
+            Ok((run, steps))
+          },
+          Message::Opened,
+        );
+      }
+      Message::Opened(res) => {
+        self.loading = false;
+        match res {
+          Ok((run, steps)) => {
+            self.steps.load(steps);
+            self.run = Some(run);
+            self.notice = None;
+          }
+          Err(e) => self.notice = Some(Err(format!("加载测试记录失败: {e}"))),
+        }
+      }
+      Message::Back => {
+        self.run = None;
+        self.notice = None;
+      }
+      Message::Export => {
+        if let Some(run) = self.run.clone() {
+          self.loading = true;
+          let cfg = self.flag.clone();
+          let records = self.steps.rows.clone();
+          return Command::perform(
+            async move { report::export_run(cfg, run, records).await.map_err(|e| e.to_string()) },
+            Message::Exported,
+          );
+        }
+      }
+      Message::Exported(res) => {
+        self.loading = false;
+        self.notice = Some(match res {
+          Ok(files) => {
+            let files: Vec<String> = files.iter().map(|x| x.display().to_string()).collect();
+            log::info(format!("重新生成测试报告: {}", files.join(", ")), Tag::AutoTest);
+            Ok(format!("已导出: {}", files.join(", ")))
+          }
+          Err(e) => Err(format!("导出报告失败: {e}")),
+        });
+      }
+      Message::Steps(e) => return self.steps.update(e).map(Message::Steps),
+      // 由主窗口打开SPC控制图
+      Message::Spc(..) => {}
+    }
+    Command::none()
+  }
+
+  fn view(&self) -> Element<'_, Self::Event> {
+    let content = match &self.run {
+      Some(run) => self.run_view(run),
+      None => column![self.filter_view(), self.runs_view()].spacing(8).into(),
+    };
+    container(column![content, self.notice_view()].spacing(6))
+      .padding(20)
+      .width(Length::Fill)
+      .height(Length::Fill)
+      .into()
+  }
+
+  fn subscription(&self) -> iced::Subscription<Self::Event> {
+    iced::Subscription::batch([])
+  }
+
+  fn close(&mut self) -> Command<Self::Event> {
+    window::close(self.id)
+  }
+
+  fn show(&self) -> Command<Self::Event> {
+    window::change_mode(self.id, window::Mode::Windowed)
+  }
+
+  fn exist(&self) -> bool {
+    self.id != window::Id::MAIN
+  }
+
+  fn focus(&mut self) -> Command<Self::Event> {
+    window::gain_focus(self.id)
+  }
+}
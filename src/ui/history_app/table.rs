@@ -0,0 +1,246 @@
+use iced::widget::{column, container, horizontal_space, mouse_area, responsive, scrollable, text};
+use iced::{Command, Length};
+
+use crate::db::r#type::run::TestStepResult;
+use crate::plugins::limit::Limit;
+use crate::ui::components::table::table::{self};
+use crate::ui::components::{Element, Renderer, Theme};
+use crate::ui::main_app::autotest::DataState;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  SyncHeader(scrollable::AbsoluteOffset),
+  Resizing(usize, f32),
+  Resized,
+  /// 选中行, 显示该次执行的原始输出; 再次选中则关闭
+  Select(usize),
+}
+
+/// 测试记录的步骤表, 每次执行一行
+pub struct Steps {
+  columns: Vec<Column>,
+  pub rows: Vec<TestStepResult>,
+  header: scrollable::Id,
+  body: scrollable::Id,
+  footer: scrollable::Id,
+  pub selected: Option<usize>,
+}
+
+impl Default for Steps {
+  fn default() -> Self {
+    Self {
+      columns: vec![
+        Column::new(ColumnKind::Index),
+        Column::new(ColumnKind::Tag),
+        Column::new(ColumnKind::Label),
+        Column::new(ColumnKind::Attempt),
+        Column::new(ColumnKind::State),
+        Column::new(ColumnKind::ResValue),
+        Column::new(ColumnKind::Limits),
+        Column::new(ColumnKind::Duration),
+      ],
+      rows: vec![],
+      header: scrollable::Id::unique(),
+      body: scrollable::Id::unique(),
+      footer: scrollable::Id::unique(),
+      selected: None,
+    }
+  }
+}
+
+impl Steps {
+  pub fn load(&mut self, rows: Vec<TestStepResult>) {
+    self.rows = rows;
+    self.selected = None;
+  }
+
+  /// 选中的步骤有数值限值时, 可查看其SPC控制图
+  pub fn spc_tag(&self) -> Option<String> {
+    let row = self.rows.get(self.selected?)?;
+    let limits = serde_json::from_value::<Limit>(row.limits.clone()).ok()?;
+    (limits.bounds() != (None, None)).then(|| row.tag.clone())
+  }
+
+  pub fn update(&mut self, message: Message) -> Command<Message> {
+    match message {
+      Message::SyncHeader(offset) => {
+        return Command::batch(vec![
+          scrollable::scroll_to(self.header.clone(), offset),
+          scrollable::scroll_to(self.footer.clone(), offset),
+        ])
+      }
+      Message::Resizing(index, offset) => {
+        if let Some(column) = self.columns.get_mut(index) {
+          column.resize_offset = Some(offset);
+        }
+      }
+      Message::Resized => self.columns.iter_mut().for_each(|column| {
+        if let Some(offset) = column.resize_offset.take() {
+          column.width += offset;
+        }
+      }),
+      Message::Select(index) => {
+        self.selected = match self.selected == Some(index) {
+          true => None,
+          false => Some(index),
+        }
+      }
+    }
+    Command::none()
+  }
+
+  /// 选中步骤的原始输出
+  fn output<'a>(&self, row: &'a TestStepResult) -> Element<'a, Message> {
+    let mut lines = column![].padding(10).spacing(2);
+    for line in row.stdout.lines() {
+      lines = lines.push(text(line).size(13));
+    }
+    for line in row.stderr.lines() {
+      lines = lines.push(text(line).size(13).style(crate::config::theme::Text::Error));
+    }
+    column![
+      text(format!("[{}] 第{}次 输出", row.tag, row.attempt)).size(14),
+      container(
+        scrollable(lines)
+          .direction(scrollable::Direction::Both {
+            vertical: scrollable::Properties::new(),
+            horizontal: scrollable::Properties::new(),
+          })
+          .width(Length::Fill)
+          .height(Length::Fill)
+          .style(crate::config::theme::Scrollable::Normal),
+      )
+      .width(Length::Fill)
+      .height(200)
+      .style(crate::config::theme::Container::Black),
+    ]
+    .spacing(4)
+    .into()
+  }
+
+  pub fn view(&self) -> Element<'_, Message> {
+    let table = responsive(|size| {
+      table::table(
+        self.header.clone(),
+        self.body.clone(),
+        &self.columns,
+        &self.rows,
+        Message::SyncHeader,
+      )
+      .on_column_resize(Message::Resizing, Message::Resized)
+      .footer(self.footer.clone())
+      .min_width(size.width)
+      .into()
+    });
+    match self.selected.and_then(|i| self.rows.get(i)) {
+      Some(row) => column![table, self.output(row)],
+      None => column![table],
+    }
+    .spacing(6)
+    .into()
+  }
+}
+
+#[derive(Debug)]
+pub struct Column {
+  kind: ColumnKind,
+  width: f32,
+  resize_offset: Option<f32>,
+}
+
+impl Column {
+  pub fn new(kind: ColumnKind) -> Self {
+    let width = match kind {
+      ColumnKind::Index => 50.0,
+      ColumnKind::Tag => 140.0,
+      ColumnKind::Label => 140.0,
+      ColumnKind::Attempt => 60.0,
+      ColumnKind::State => 100.0,
+      ColumnKind::ResValue => 200.0,
+      ColumnKind::Limits => 150.0,
+      ColumnKind::Duration => 80.0,
+    };
+    Self {
+      kind,
+      width,
+      resize_offset: None,
+    }
+  }
+}
+
+#[derive(Debug)]
+pub enum ColumnKind {
+  Index,
+  Tag,
+  Label,
+  Attempt,
+  State,
+  ResValue,
+  Limits,
+  Duration,
+}
+
+impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
+  type Row = TestStepResult;
+  fn header(&'a self, _col_index: usize) -> Element<'a, Message> {
+    let content = match self.kind {
+      ColumnKind::Index => "序号",
+      ColumnKind::Tag => "标签",
+      ColumnKind::Label => "名称",
+      ColumnKind::Attempt => "次数",
+      ColumnKind::State => "状态",
+      ColumnKind::ResValue => "结果值",
+      ColumnKind::Limits => "限值",
+      ColumnKind::Duration => "耗时",
+    };
+    container(text(content))
+      .height(24)
+      .width(Length::Fill)
+      .center_x()
+      .center_y()
+      .into()
+  }
+
+  fn cell(&'a self, _col_index: usize, row_index: usize, row: &'a Self::Row) -> Element<'a, Message> {
+    let limits = || serde_json::from_value::<Limit>(row.limits.clone()).unwrap_or_default();
+    let content: Element<'_, _> = match self.kind {
+      ColumnKind::Index => text(row_index + 1).into(),
+      ColumnKind::Tag => text(&row.tag).into(),
+      ColumnKind::Label => text(&row.label).into(),
+      ColumnKind::Attempt => text(row.attempt).into(),
+      ColumnKind::State => DataState::from(row.state.as_str()).to_container(15).into(),
+      ColumnKind::ResValue => match &row.violation {
+        Some(violation) => column![
+          text(limits().format_value(&row.res_value)),
+          text(violation).size(12).style(crate::config::theme::Text::Error),
+        ]
+        .into(),
+        None => text(limits().format_value(&row.res_value)).into(),
+      },
+      ColumnKind::Limits => text(limits().to_string()).into(),
+      ColumnKind::Duration => text(format!("{:.2}s", row.duration_ms as f64 / 1000.0)).into(),
+    };
+    mouse_area(container(content).width(Length::Fill).height(32).center_x().center_y())
+      .on_press(Message::Select(row_index))
+      .into()
+  }
+
+  fn footer(&'a self, _col_index: usize, rows: &'a [Self::Row]) -> Option<Element<'a, Message>> {
+    let content = match self.kind {
+      ColumnKind::State => {
+        let failed = rows.iter().filter(|x| !matches!(x.state.as_str(), "Pass" | "Skip")).count();
+        Element::from(text(format!("共{} 失败{failed}", rows.len())))
+      }
+      _ => horizontal_space().into(),
+    };
+    Some(container(content).height(24).center_y().into())
+  }
+
+  fn width(&self) -> f32 {
+    self.width
+  }
+
+  fn resize_offset(&self) -> Option<f32> {
+    self.resize_offset
+  }
+}
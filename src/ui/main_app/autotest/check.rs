@@ -0,0 +1,53 @@
+use iced::{
+  widget::{column, container, horizontal_space, row, scrollable, text, Column},
+  Alignment, Length,
+};
+
+use super::Message;
+use crate::{
+  config::theme,
+  data::icon,
+  plugins::validate::{Level, Report},
+  ui::components::{
+    helpers::{button2, text_with_logo},
+    Element,
+  },
+};
+
+/// 运行前检查或预演结果
+pub fn view<'a>(title: &'a str, report: &'a Report) -> Element<'a, Message> {
+  let lines = Column::with_children(report.issues.iter().map(|issue| {
+    let t = text(issue.to_string()).size(14);
+    match issue.level {
+      Level::Error => t.style(theme::Text::Error),
+      Level::Warning => t.style(theme::Text::Warning),
+      Level::Info => t.style(theme::Text::Default),
+    }
+    .into()
+  }))
+  .spacing(4)
+  .padding(10);
+  let summary = match report.has_errors() {
+    true => text("存在错误, 无法开始测试").style(theme::Text::Error),
+    false => text(format!("共{}项", report.issues.len())),
+  };
+  column![
+    row![
+      text(title).size(18),
+      summary,
+      horizontal_space(),
+      button2(text_with_logo(icon::close_circle(), "关闭"))
+        .width(110)
+        .on_press(Message::CloseReport),
+    ]
+    .spacing(15)
+    .align_items(Alignment::Center),
+    container(scrollable(lines).style(theme::Scrollable::Normal))
+      .width(Length::Fill)
+      .height(Length::Fill)
+      .style(theme::Container::Frame),
+  ]
+  .spacing(8)
+  .padding(20)
+  .into()
+}
@@ -1,71 +1,174 @@
+mod check;
+mod prompt;
 mod table;
 pub mod theme;
 use super::AppWindow;
 use crate::{
-  config::Config,
-  db::r#type::app::ExtendApp,
+  config::{
+    logger::{log, Tag},
+    Config,
+  },
+  data::DB_SQLITE,
+  db::{
+    apis::{app::select_all_extend_apps, run::exist_run_result},
+    r#type::{app::ExtendApp, run::TestRun},
+  },
+  plugins::{
+    control::{Control, RunControl},
+    executor::Stream,
+    hook::Hooks,
+    schedule::{schedule, select},
+    sequence::{self, Attempt, Progress},
+    spc,
+    step::{
+      self,
+      prompt::{Answer, Prompt},
+    },
+    template::Template,
+    upload,
+    validate::{dry_run, validate, Level, Report},
+  },
+  utils::time::Time,
+  data::icon,
   ui::components::{
-    helpers::{button2, text2},
+    helpers::{button2, text_with_logo},
     Container, Element, Text,
   },
 };
-use iced::widget::{column, horizontal_space, row};
+use iced::widget::{column, horizontal_space, row, scrollable, text_input, Column};
 use iced::{
   widget::{container, text},
   window, Command, Length, Size,
 };
+use e_utils::{
+  parse::{MyParseFormat as _, ParseResultDefault as _},
+  regex::Regex,
+  time::Utc,
+};
 use serde_json::Value;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// 每个步骤保留的输出行数
+const MAX_OUTPUT_LINES: usize = 1000;
 
 #[derive(Clone, Debug)]
 pub enum Message {
   TableWindow(table::Message),
   Submit,
+  Loaded(Result<Vec<ExtendApp>, String>),
+  Start(Result<Vec<ExtendApp>, String>),
+  Progress(Progress),
+  Tick,
+  /// 运行控制: 暂停、继续、单步、中止
+  Control(Control),
+  /// 操作员提示输入
+  PromptInput(String),
+  /// 回答操作员提示
+  PromptAnswer(u64, Answer),
+  /// SN输入, 扫码枪以回车结束
+  SnInput(String),
+  SnSubmit,
+  SnChecked(Result<String, String>),
+  /// 预演, 列出替换变量后的命令, 不执行
+  DryRun,
+  DryRunLoaded(Result<Vec<ExtendApp>, String>),
+  CloseReport,
+  /// 发送待上传的结果
+  Flush,
+  /// 剩余待上传及已放弃上传的数量
+  Flushed(Result<(i64, i64), String>),
+  /// 已放弃上传的结果重新加入队列
+  Requeue,
+  /// 查看选中步骤的SPC控制图, 由主窗口打开
+  Spc(String),
+  /// 测试结束后的SPC判异
+  SpcChecked(Report),
 }
 
 #[derive(Debug, Clone)]
 pub struct Data {
   pub state: DataState,
-  pub value: Value,
   pub res_value: Value,
+  /// 违反的限值
+  pub violation: Option<String>,
+  /// 耗时
+  pub time: Time,
+  /// 每次执行的记录
+  pub attempts: Vec<Attempt>,
+  /// 实时输出
+  pub output: Vec<(Stream, String)>,
   pub extend_app: ExtendApp,
 }
-impl Data {
-  pub fn generate(i: i32) -> Self {
+impl From<ExtendApp> for Data {
+  fn from(extend_app: ExtendApp) -> Self {
     Self {
-      state: match i % 2 {
-        0 if i == 0 => DataState::Ready,
-        1 => DataState::Fail,
-        _ => DataState::Success,
-      },
-      value: Value::Number(i.into()),
+      state: DataState::Ready,
       res_value: Value::Null,
-      extend_app: ExtendApp {
-        tag: format!("{i}"),
-        ..Default::default()
-      },
+      violation: None,
+      time: Time::init(),
+      attempts: vec![],
+      output: vec![],
+      extend_app,
     }
   }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataState {
   Ready,
+  Running,
   Success,
   Fail,
+  /// 执行超时, 进程树已结束
+  Timeout,
+  /// 前置步骤未通过
+  Skipped,
+  /// 被中止
+  Aborted,
+}
+impl From<&str> for DataState {
+  fn from(value: &str) -> Self {
+    match value {
+      "Running" => DataState::Running,
+      "Pass" => DataState::Success,
+      "Fail" => DataState::Fail,
+      "Timeout" => DataState::Timeout,
+      "Skip" => DataState::Skipped,
+      "Abort" => DataState::Aborted,
+      _ => DataState::Ready,
+    }
+  }
 }
 impl DataState {
-  pub fn to_text<'a>(&'a self, size: impl Into<iced::Pixels>) -> Text<'a> {
+  /// 显示及存储的名称
+  pub fn as_str(&self) -> &'static str {
     match self {
-      DataState::Ready => text("Ready").size(size).style(crate::config::theme::Text::Default),
-      DataState::Success => text("Pass").size(size).style(crate::config::theme::Text::Success),
-      DataState::Fail => text("Fail").size(size).style(crate::config::theme::Text::Error),
+      DataState::Ready => "Ready",
+      DataState::Running => "Running",
+      DataState::Success => "Pass",
+      DataState::Fail => "Fail",
+      DataState::Timeout => "Timeout",
+      DataState::Skipped => "Skip",
+      DataState::Aborted => "Abort",
+    }
+  }
+  pub fn to_text<'a>(&self, size: impl Into<iced::Pixels>) -> Text<'a> {
+    let t = text(self.as_str()).size(size);
+    match self {
+      DataState::Ready => t.style(crate::config::theme::Text::Default),
+      DataState::Running | DataState::Skipped => t.style(crate::config::theme::Text::Warning),
+      DataState::Success => t.style(crate::config::theme::Text::Success),
+      DataState::Fail | DataState::Timeout | DataState::Aborted => t.style(crate::config::theme::Text::Error),
     }
     .into()
   }
-  pub fn to_container<'a, Event>(&'a self, size: impl Into<iced::Pixels>) -> Container<'a, Event> {
+  pub fn to_container<'a, Event>(&self, size: impl Into<iced::Pixels>) -> Container<'a, Event> {
+    let c = container(text(self.as_str()).size(size));
     match self {
-      DataState::Ready => container(text("Ready").size(size)).style(crate::config::theme::Container::Default),
-      DataState::Success => container(text("Pass").size(size)).style(crate::config::theme::Container::Success),
-      DataState::Fail => container(text("Fail").size(size)).style(crate::config::theme::Container::Error),
+      DataState::Ready => c.style(crate::config::theme::Container::Default),
+      DataState::Running => c.style(crate::config::theme::Container::Warn),
+      DataState::Success => c.style(crate::config::theme::Container::Success),
+      DataState::Fail | DataState::Timeout => c.style(crate::config::theme::Container::Error),
+      DataState::Skipped | DataState::Aborted => c.style(crate::config::theme::Container::Frame),
     }
     .center_x()
     .center_y()
@@ -79,14 +182,287 @@ pub struct App {
   pub id: window::Id,
   pub state: DataState,
   pub table_window: table::App,
+  /// 序列运行ID, 每次启动递增
+  pub run_id: usize,
+  pub running: bool,
+  /// 已提交SN, 等待检查或加载步骤; 期间不再接受提交, 避免重复启动
+  pub submitting: bool,
+  /// 本次运行的步骤, 订阅只持有其引用, 不复制表格中的输出
+  pub steps: Arc<Vec<ExtendApp>>,
+  /// 当前测试记录
+  pub run: TestRun,
+  pub control: RunControl,
+  /// 步骤模板变量
+  pub template: Template,
+  /// 等待回答的操作员提示
+  pub prompt: Option<Prompt>,
+  pub prompt_input: String,
+  /// 被测件SN
+  pub sn: String,
+  pub sn_id: text_input::Id,
+  pub sn_error: Option<String>,
+  /// 运行前检查或预演结果
+  pub report: Option<(&'static str, Report)>,
+  /// 待上传数量
+  pub outbox: i64,
+  /// 已放弃上传的数量
+  pub outbox_dead: i64,
 }
 impl Default for App {
   fn default() -> Self {
     Self {
       id: window::Id::MAIN,
       flag: Config::default(),
-      state: DataState::Success,
+      state: DataState::Ready,
       table_window: table::App::new(Config::default()),
+      run_id: 0,
+      running: false,
+      submitting: false,
+      steps: Default::default(),
+      run: TestRun::default(),
+      control: RunControl::default(),
+      template: Template::default(),
+      prompt: None,
+      prompt_input: String::new(),
+      sn: String::new(),
+      sn_id: text_input::Id::unique(),
+      sn_error: None,
+      report: None,
+      outbox: 0,
+      outbox_dead: 0,
+    }
+  }
+}
+
+impl App {
+  /// 加载工站`steps`中已启用的步骤, 按依赖和优先级排序; `steps`为空时加载全部
+  pub fn load(steps: Vec<String>, on_load: fn(Result<Vec<ExtendApp>, String>) -> Message) -> Command<Message> {
+    Command::perform(
+      async move {
+        let pool = DB_SQLITE.read().await.pool();
+        let apps = select_all_extend_apps(&pool).await.map_err(|e| e.to_string())?;
+        select(apps, &steps).and_then(schedule).map_err(|e| e.to_string())
+      },
+      on_load,
+    )
+  }
+
+  /// 重新加载步骤表, 运行中不加载
+  pub fn reload(&self) -> Command<Message> {
+    match self.running {
+      true => Command::none(),
+      false => Self::load(self.steps(), Message::Loaded),
+    }
+  }
+
+  /// 发送待上传的结果
+  pub fn flush(&self) -> Command<Message> {
+    let cfg = self.flag.mes.clone();
+    Command::perform(
+      async move {
+        let pool = DB_SQLITE.read().await.pool();
+        upload::flush(&pool, &cfg).await.map_err(|e| e.to_string())
+      },
+      Message::Flushed,
+    )
+  }
+
+  /// 待上传及已放弃上传的数量
+  pub fn outbox_view(&self) -> Element<'_, Message> {
+    let t = text(format!("待上传: {}", self.outbox));
+    let pending: Element<'_, Message> = match self.outbox {
+      0 => t.into(),
+      _ => t.style(crate::config::theme::Text::Warning).into(),
+    };
+    match self.outbox_dead {
+      0 => pending,
+      dead => row![
+        pending,
+        text(format!("上传失败: {dead}")).style(crate::config::theme::Text::Error),
+        button2(text_with_logo(icon::sync(), "重新上传")).on_press(Message::Requeue),
+      ]
+      .spacing(10)
+      .align_items(iced::Alignment::Center)
+      .into(),
+    }
+  }
+
+  /// 步骤模板变量
+  fn new_template(&self) -> Template {
+    Template::new(
+      self.flag.clone(),
+      HashMap::from([
+        ("sn".to_string(), self.sn.clone()),
+        ("station".to_string(), self.flag.station_name().to_string()),
+        ("operator".to_string(), self.flag.user.name.clone()),
+        ("date".to_string(), "{date}".parse_format().def()),
+      ]),
+    )
+  }
+
+  /// 显示检查结果并写入日志
+  fn show_report(&mut self, title: &'static str, report: Report) {
+    for issue in &report.issues {
+      match issue.level {
+        Level::Error => log::error(issue.to_string(), Tag::AutoTest),
+        Level::Warning => log::warn(issue.to_string(), Tag::AutoTest),
+        Level::Info => log::info(issue.to_string(), Tag::AutoTest),
+      }
+    }
+    self.report = Some((title, report));
+  }
+
+  /// 当前工站的步骤
+  pub fn steps(&self) -> Vec<String> {
+    self.flag.station().map(|x| x.steps.clone()).unwrap_or_default()
+  }
+
+  /// 校验SN: 工站或产品规则及本工站是否已通过
+  async fn check_sn(sn: String, pattern: Option<String>, station: String) -> Result<String, String> {
+    let sn = sn.trim().to_string();
+    if sn.is_empty() {
+      return Err("请输入SN".to_string());
+    }
+    if let Some(pattern) = pattern.filter(|x| !x.is_empty()) {
+      let re = Regex::new(&pattern).map_err(|e| format!("SN规则无效 {pattern}: {e}"))?;
+      if !re.is_match(&sn) {
+        return Err(format!("SN格式不符: {sn}"));
+      }
+    }
+    let pool = DB_SQLITE.read().await.pool();
+    let passed = exist_run_result(&pool, &sn, &station, DataState::Success.as_str())
+      .await
+      .map_err(|e| e.to_string())?;
+    if passed {
+      return Err(format!("SN已在本工站测试通过: {sn}"));
+    }
+    Ok(sn)
+  }
+
+  /// SN输入栏
+  pub fn sn_bar(&self) -> Element<'_, Message> {
+    let mut input = text_input("扫描或输入SN后回车", &self.sn)
+      .id(self.sn_id.clone())
+      .on_submit(Message::SnSubmit)
+      .width(320);
+    if !self.running {
+      input = input.on_input(Message::SnInput);
+    }
+    let mut bar = row![text("SN:"), input].spacing(10).align_items(iced::Alignment::Center);
+    if let Some(e) = &self.sn_error {
+      bar = bar.push(text(e).style(crate::config::theme::Text::Error));
+    }
+    bar.into()
+  }
+
+  /// 选中的步骤有数值限值时, 可查看其SPC控制图
+  fn spc_tag(&self) -> Option<String> {
+    let row = self.table_window.rows.get(self.table_window.selected?)?;
+    (row.extend_app.limits.bounds() != (None, None)).then(|| row.extend_app.tag.clone())
+  }
+
+  /// 检查本次通过或失败的数值步骤是否出现过程异常
+  fn check_spc(&self) -> Command<Message> {
+    let tags: Vec<String> = self
+      .table_window
+      .rows
+      .iter()
+      .filter(|x| matches!(x.state, DataState::Success | DataState::Fail))
+      .filter(|x| x.extend_app.limits.bounds() != (None, None))
+      .map(|x| x.extend_app.tag.clone())
+      .collect();
+    if tags.is_empty() {
+      return Command::none();
+    }
+    Command::perform(spc::check(tags, self.flag.station_name().to_string()), Message::SpcChecked)
+  }
+
+  /// 运行变量侧栏
+  fn vars_panel(&self) -> Option<Element<'_, Message>> {
+    let vars = self.template.vars();
+    if vars.is_empty() {
+      return None;
+    }
+    let list = Column::with_children(vars.into_iter().map(|(k, v)| {
+      column![text(format!("#{k}#")).size(13), text(v).size(14).style(crate::config::theme::Text::Success)]
+        .spacing(2)
+        .into()
+    }))
+    .padding(10)
+    .spacing(8);
+    Some(
+      column![
+        text("运行变量").size(15),
+        container(scrollable(list).height(Length::Fill).style(crate::config::theme::Scrollable::Normal))
+          .height(Length::Fill)
+          .width(Length::Fill)
+          .style(crate::config::theme::Container::Frame),
+      ]
+      .spacing(6)
+      .padding([20, 20, 20, 0])
+      .width(240)
+      .into(),
+    )
+  }
+
+  /// 更新执行进度
+  fn progress(&mut self, progress: Progress) {
+    let ref mut rows = self.table_window.rows;
+    match progress {
+      Progress::Created(id) => self.run.id = id,
+      Progress::Started(index) => {
+        if let Some(row) = rows.get_mut(index) {
+          row.state = DataState::Running;
+          row.time.start();
+          row.output.clear();
+        }
+      }
+      Progress::Output(index, stream, line) => {
+        if let Some(row) = rows.get_mut(index) {
+          let msg = format!("[{}] {line}", row.extend_app.tag);
+          match stream {
+            Stream::Stdout => log::info(msg, Tag::AutoTest),
+            Stream::Stderr => log::warn(msg, Tag::AutoTest),
+          }
+          if row.output.len() >= MAX_OUTPUT_LINES {
+            row.output.drain(0..MAX_OUTPUT_LINES / 10);
+          }
+          row.output.push((stream, line));
+        }
+      }
+      Progress::Finished(index, res) => {
+        if let Some(row) = rows.get_mut(index) {
+          log::info(
+            format!(
+              "[{}] {:?} 共{}次 {}",
+              row.extend_app.tag,
+              res.state,
+              res.attempts.len(),
+              res.res_value
+            ),
+            Tag::AutoTest,
+          );
+          if let Some(violation) = &res.violation {
+            log::warn(format!("[{}] {violation}", row.extend_app.tag), Tag::AutoTest);
+          }
+          if row.state == DataState::Running {
+            row.time.stop();
+          }
+          row.state = res.state;
+          row.res_value = res.res_value;
+          row.violation = res.violation.map(|x| x.to_string());
+          row.attempts = res.attempts;
+        }
+      }
+      Progress::Done(result) => {
+        self.running = false;
+        self.prompt = None;
+        self.state = result;
+        self.run.result = self.state.as_str().to_string();
+        self.run.ended_at = Some(Utc::now());
+        self.sn.clear();
+        log::info(format!("测试完成[{}]: {:?}", self.run.id, self.state), Tag::AutoTest);
+      }
     }
   }
 }
@@ -139,24 +515,262 @@ impl AppWindow for App {
         let command = self.table_window.update(event);
         command.map(Message::TableWindow)
       }
-      _ => Command::none(),
+      Message::Submit => {
+        if self.running {
+          self.submitting = false;
+          return Command::none();
+        }
+        self.report = None;
+        Self::load(self.steps(), Message::Start)
+      }
+      Message::DryRun => {
+        if self.running {
+          return Command::none();
+        }
+        Self::load(self.steps(), Message::DryRunLoaded)
+      }
+      Message::DryRunLoaded(res) => {
+        let report = match res {
+          Ok(apps) => {
+            let mut report = validate(&apps);
+            report.issues.extend(dry_run(&apps, &self.new_template()).issues);
+            report
+          }
+          Err(e) => Report::error(format!("加载测试步骤失败: {e}")),
+        };
+        self.show_report("预演", report);
+        Command::none()
+      }
+      Message::CloseReport => {
+        self.report = None;
+        Command::none()
+      }
+      Message::Flush => self.flush(),
+      Message::Requeue => {
+        let cfg = self.flag.mes.clone();
+        Command::perform(
+          async move {
+            let pool = DB_SQLITE.read().await.pool();
+            upload::requeue(&pool).await?;
+            upload::flush(&pool, &cfg).await
+          },
+          |res| Message::Flushed(res.map_err(|e| e.to_string())),
+        )
+      }
+      Message::SpcChecked(report) => {
+        // 已开始下一次测试时只写日志, 不打断测试表
+        if self.running || self.prompt.is_some() {
+          report.issues.iter().for_each(|x| log::warn(x.to_string(), Tag::AutoTest));
+        } else if !report.issues.is_empty() {
+          self.show_report("SPC预警", report);
+        }
+        Command::none()
+      }
+      // 由主窗口打开SPC控制图
+      Message::Spc(_) => Command::none(),
+      Message::Flushed(res) => {
+        match res {
+          Ok((pending, dead)) => {
+            self.outbox = pending;
+            self.outbox_dead = dead;
+          }
+          Err(e) => log::error(format!("上传结果失败: {e}"), Tag::AutoTest),
+        }
+        Command::none()
+      }
+      Message::Loaded(res) => {
+        match res {
+          Ok(apps) => self.table_window.rows = apps.into_iter().map(Data::from).collect(),
+          Err(e) => log::error(format!("加载测试步骤失败: {e}"), Tag::AutoTest),
+        }
+        Command::none()
+      }
+      Message::Start(res) => {
+        self.submitting = false;
+        // 运行中再次启动会替换订阅, 丢弃正在运行的序列
+        if self.running {
+          return Command::none();
+        }
+        let res = res.map_err(|e| Report::error(format!("加载测试步骤失败: {e}"))).and_then(|apps| {
+          let report = validate(&apps);
+          match report.has_errors() {
+            true => Err(report),
+            false => Ok((apps, report)),
+          }
+        });
+        match res {
+          Ok((apps, report)) => {
+            for issue in &report.issues {
+              log::warn(issue.to_string(), Tag::AutoTest);
+            }
+            self.table_window.rows = apps.iter().cloned().map(Data::from).collect();
+            self.steps = Arc::new(apps);
+            self.run_id += 1;
+            self.running = true;
+            self.control = RunControl::default();
+            self.run = TestRun {
+              sn: self.sn.clone(),
+              station: self.flag.station_name().to_string(),
+              user_id: self.flag.user.id,
+              result: DataState::Running.as_str().to_string(),
+              started_at: Utc::now(),
+              ..Default::default()
+            };
+            self.template = self.new_template();
+            self.state = DataState::Running;
+            log::info(format!("开始测试: 共{}项", self.table_window.rows.len()), Tag::AutoTest);
+          }
+          Err(report) => self.show_report("运行前检查", report),
+        }
+        Command::none()
+      }
+      Message::Progress(progress) => {
+        let done = matches!(progress, Progress::Done(_));
+        self.progress(progress);
+        if done {
+          return Command::batch([text_input::focus(self.sn_id.clone()), self.flush(), self.check_spc()]);
+        }
+        Command::none()
+      }
+      Message::SnInput(sn) => {
+        self.sn = sn;
+        self.sn_error = None;
+        Command::none()
+      }
+      Message::SnSubmit => {
+        if self.running || self.submitting {
+          return Command::none();
+        }
+        self.submitting = true;
+        let pattern = self.flag.sn_pattern().map(String::from);
+        Command::perform(
+          Self::check_sn(self.sn.clone(), pattern, self.flag.station_name().to_string()),
+          Message::SnChecked,
+        )
+      }
+      Message::SnChecked(res) => match res {
+        Ok(sn) => {
+          self.sn = sn;
+          self.sn_error = None;
+          self.update(Message::Submit)
+        }
+        Err(e) => {
+          self.submitting = false;
+          log::warn(e.clone(), Tag::AutoTest);
+          self.sn_error = Some(e);
+          self.sn.clear();
+          text_input::focus(self.sn_id.clone())
+        }
+      },
+      Message::Tick => {
+        let prompt = step::prompt::pending();
+        if prompt.as_ref().map(|x| x.id) != self.prompt.as_ref().map(|x| x.id) {
+          self.prompt_input.clear();
+        }
+        self.prompt = prompt;
+        Command::none()
+      }
+      Message::PromptInput(input) => {
+        self.prompt_input = input;
+        Command::none()
+      }
+      Message::PromptAnswer(id, answer) => {
+        if let Some(prompt) = &self.prompt {
+          log::info(format!("[{}] 操作员回答: {answer:?}", prompt.title), Tag::AutoTest);
+        }
+        step::prompt::answer(id, answer);
+        self.prompt = None;
+        self.prompt_input.clear();
+        Command::none()
+      }
+      Message::Control(control) => {
+        if self.running {
+          log::info(format!("运行控制: {control:?}"), Tag::AutoTest);
+          self.control.set(control);
+        }
+        Command::none()
+      }
     }
   }
 
   /// 日志窗口组件
   fn view(&self) -> Element<'_, Self::Event> {
+    let running = self.running;
+    let control = self.control.get();
+    let paused = running && control == Control::Pause;
+    let on = |enable: bool, message: Message| enable.then_some(message);
     let submit = row![
       horizontal_space(),
-      button2(text2("提交").size(25)).width(150).on_press(Message::Submit),
+      button2(text_with_logo(icon::play_circle(), "开始"))
+        .width(110)
+        .on_press_maybe(on(!running && !self.submitting, Message::SnSubmit)),
+      button2(text_with_logo(icon::filter(), "预演"))
+        .width(110)
+        .on_press_maybe(on(!running, Message::DryRun)),
+      if paused {
+        button2(text_with_logo(icon::play_circle(), "继续"))
+          .width(110)
+          .on_press(Message::Control(Control::Run))
+      } else {
+        button2(text_with_logo(icon::pause(), "暂停"))
+          .width(110)
+          .on_press_maybe(on(running && control == Control::Run, Message::Control(Control::Pause)))
+      },
+      button2(text_with_logo(icon::redo(), "单步"))
+        .width(110)
+        .on_press_maybe(on(paused, Message::Control(Control::Step))),
+      button2(text_with_logo(icon::stop(), "中止"))
+        .width(110)
+        .style(crate::config::theme::Button::Delete)
+        .on_press_maybe(on(running && control != Control::Abort, Message::Control(Control::Abort))),
+      button2(text_with_logo(icon::layout(), "SPC"))
+        .width(110)
+        .on_press_maybe(self.spc_tag().map(Message::Spc)),
       horizontal_space(),
     ]
+    .spacing(15)
+    .align_items(iced::Alignment::Center)
     .height(60);
 
-    column![self.table_window.view().map(Message::TableWindow), submit].into()
+    let table = match (&self.prompt, &self.report) {
+      (Some(p), _) => prompt::view(p, &self.prompt_input),
+      (None, Some((title, report))) => check::view(title, report),
+      (None, None) => self.table_window.view().map(Message::TableWindow),
+    };
+    match self.vars_panel() {
+      Some(vars) => column![row![table, vars], submit].into(),
+      None => column![table, submit].into(),
+    }
   }
 
   fn subscription(&self) -> iced::Subscription<Self::Event> {
-    iced::Subscription::batch([])
+    // 后台重试上传
+    let retry = Duration::from_secs(self.flag.mes.retry_interval.max(5) as u64);
+    let flush = iced::time::every(retry).map(|_| Message::Flush);
+    if self.running {
+      let max_parallel = self.flag.runner.max_parallel.max(1) as usize;
+      let station = self.flag.station();
+      iced::Subscription::batch([
+        sequence::run(
+          self.run_id,
+          self.steps.clone(),
+          max_parallel,
+          self.run.clone(),
+          self.control.clone(),
+          self.template.clone(),
+          Hooks {
+            pre: station.map(|x| x.pre_hook.clone()).unwrap_or_default(),
+            post: station.map(|x| x.post_hook.clone()).unwrap_or_default(),
+          },
+        )
+        .map(Message::Progress),
+        // 刷新运行中步骤的耗时
+        iced::time::every(Duration::from_millis(200)).map(|_| Message::Tick),
+        flush,
+      ])
+    } else {
+      flush
+    }
   }
 
   fn close(&mut self) -> Command<Self::Event> {
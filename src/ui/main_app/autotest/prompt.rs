@@ -0,0 +1,86 @@
+use iced::{
+  widget::{column, container, horizontal_space, image, row, text, text_input},
+  Alignment, Length,
+};
+
+use super::Message;
+use crate::{
+  data::{icon, IMG_MAP, ORIGIN},
+  plugins::step::prompt::{Answer, Prompt, PromptInput},
+  ui::components::{
+    helpers::{button2, text_with_logo},
+    Element,
+  },
+};
+
+/// 图片, 优先使用内置图片
+fn handle(src: &str) -> image::Handle {
+  match IMG_MAP.get(src) {
+    Some(handle) => handle.clone(),
+    None => image::Handle::from_path(ORIGIN.join(src)),
+  }
+}
+
+/// 操作员提示模态框
+pub fn view<'a>(prompt: &'a Prompt, input: &'a str) -> Element<'a, Message> {
+  let fail = button2(text_with_logo(icon::close_circle(), "不通过"))
+    .width(120)
+    .style(crate::config::theme::Button::Delete)
+    .on_press(Message::PromptAnswer(prompt.id, Answer::Fail));
+  let answer: Element<'_, Message> = match prompt.input {
+    PromptInput::Confirm => row![
+      horizontal_space(),
+      button2(text_with_logo(icon::check_circle(), "通过"))
+        .width(120)
+        .style(crate::config::theme::Button::Success)
+        .on_press(Message::PromptAnswer(prompt.id, Answer::Pass)),
+      fail,
+      horizontal_space(),
+    ]
+    .spacing(20)
+    .into(),
+    PromptInput::Text | PromptInput::Number => {
+      let valid = match prompt.input {
+        PromptInput::Number => input.trim().parse::<f64>().is_ok(),
+        _ => !input.trim().is_empty(),
+      };
+      let submit = valid.then(|| Message::PromptAnswer(prompt.id, Answer::Value(input.trim().to_string())));
+      let mut field = text_input(if prompt.input == PromptInput::Number { "0.0" } else { "" }, input)
+        .on_input(Message::PromptInput)
+        .width(260);
+      if let Some(submit) = submit.clone() {
+        field = field.on_submit(submit);
+      }
+      row![
+        field,
+        button2(text_with_logo(icon::check_circle(), "确定"))
+          .width(120)
+          .on_press_maybe(submit),
+        fail,
+      ]
+      .spacing(20)
+      .align_items(Alignment::Center)
+      .into()
+    }
+  };
+  let mut card = column![
+    text(&prompt.title).size(22),
+    text(&prompt.message).size(18)
+  ]
+  .spacing(16)
+  .align_items(Alignment::Center);
+  if let Some(src) = prompt.image.as_deref().filter(|x| !x.is_empty()) {
+    card = card.push(image(handle(src)).height(300));
+  }
+  container(
+    container(card.push(answer))
+      .padding(30)
+      .max_width(700)
+      .style(crate::config::theme::Container::Frame),
+  )
+  .width(Length::Fill)
+  .height(Length::Fill)
+  .center_x()
+  .center_y()
+  .into()
+}
@@ -2,7 +2,10 @@ use crate::config::Config;
 use crate::ui::components::helpers::text2;
 use crate::ui::components::table::table::{self};
 use crate::ui::main_app::AppWindow;
-use iced::widget::{checkbox, column, container, horizontal_space, responsive, row, scrollable, text};
+use crate::plugins::executor::Stream;
+use iced::widget::{
+  checkbox, column, container, horizontal_space, mouse_area, responsive, row, scrollable, text, Column as Col,
+};
 use iced::{window, Command, Length, Size};
 
 use crate::ui::components::{Element, Renderer, Theme};
@@ -15,11 +18,13 @@ pub enum Message {
   ResizeColumnsEnabled(bool),
   FooterEnabled(bool),
   MinWidthEnabled(bool),
+  /// 选中行, 显示该步骤的输出; 再次选中则关闭
+  Select(usize),
 }
 pub struct App {
   id: window::Id,
   columns: Vec<Column>,
-  rows: Vec<super::Data>,
+  pub rows: Vec<super::Data>,
   header: scrollable::Id,
   body: scrollable::Id,
   footer: scrollable::Id,
@@ -27,6 +32,8 @@ pub struct App {
   footer_enabled: bool,
   min_width_enabled: bool,
   title: String,
+  /// 选中的行
+  pub selected: Option<usize>,
   pub flag: Config,
 }
 
@@ -38,6 +45,7 @@ impl Default for App {
         Column::new(ColumnKind::Index),
         Column::new(ColumnKind::Label),
         Column::new(ColumnKind::State),
+        Column::new(ColumnKind::Elapsed),
         Column::new(ColumnKind::Value),
         Column::new(ColumnKind::ResValue),
         Column::new(ColumnKind::Type),
@@ -48,7 +56,7 @@ impl Default for App {
         Column::new(ColumnKind::Timeout),
         Column::new(ColumnKind::Count),
       ],
-      rows: (0..150).map(super::Data::generate).collect(),
+      rows: vec![],
       header: scrollable::Id::unique(),
       body: scrollable::Id::unique(),
       footer: scrollable::Id::unique(),
@@ -56,11 +64,45 @@ impl Default for App {
       footer_enabled: false,
       min_width_enabled: true,
       title: String::new(),
+      selected: None,
       flag: Config::default(),
     }
   }
 }
 
+impl App {
+  /// 选中步骤的实时输出
+  fn output<'a>(&self, row: &'a super::Data) -> Element<'a, Message> {
+    let lines = Col::with_children(row.output.iter().map(|(stream, line)| {
+      let style = match stream {
+        Stream::Stdout => crate::config::theme::Text::Default,
+        Stream::Stderr => crate::config::theme::Text::Error,
+      };
+      text(line).size(13).style(style).into()
+    }))
+    .padding(10)
+    .spacing(2);
+    column![
+      text(format!("[{}] 输出 ({}行)", row.extend_app.tag, row.output.len())).size(14),
+      container(
+        scrollable(lines)
+          .direction(scrollable::Direction::Both {
+            vertical: scrollable::Properties::new().alignment(scrollable::Alignment::End),
+            horizontal: scrollable::Properties::new(),
+          })
+          .width(Length::Fill)
+          .height(Length::Fill)
+          .style(crate::config::theme::Scrollable::Normal),
+      )
+      .width(Length::Fill)
+      .height(200)
+      .style(crate::config::theme::Container::Black),
+    ]
+    .spacing(4)
+    .into()
+  }
+}
+
 impl AppWindow for App {
   type Event = Message;
   type Flag = Config;
@@ -123,6 +165,12 @@ impl AppWindow for App {
       Message::ResizeColumnsEnabled(enabled) => self.resize_columns_enabled = enabled,
       Message::FooterEnabled(enabled) => self.footer_enabled = enabled,
       Message::MinWidthEnabled(enabled) => self.min_width_enabled = enabled,
+      Message::Select(index) => {
+        self.selected = match self.selected == Some(index) {
+          true => None,
+          false => Some(index),
+        }
+      }
     }
 
     Command::none()
@@ -150,7 +198,11 @@ impl AppWindow for App {
       table.into()
     });
 
-    let content = column![table].spacing(6);
+    let content = match self.selected.and_then(|i| self.rows.get(i)) {
+      Some(row) => column![table, self.output(row)],
+      None => column![table],
+    }
+    .spacing(6);
 
     container(container(content).width(Length::Fill).height(Length::Fill))
       .padding(20)
@@ -194,6 +246,7 @@ impl Column {
       ColumnKind::Index => 50.0,
       ColumnKind::Label => 140.0,
       ColumnKind::State => 140.0,
+      ColumnKind::Elapsed => 80.0,
       ColumnKind::Type => 60.0,
       ColumnKind::Value => 150.0,
       ColumnKind::ResValue => 150.0,
@@ -217,6 +270,7 @@ pub enum ColumnKind {
   Index,
   Label,
   State,
+  Elapsed,
   Value,
   ResValue,
   Type,
@@ -235,6 +289,7 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
       ColumnKind::Index => "序号",
       ColumnKind::Label => "标签",
       ColumnKind::State => "状态",
+      ColumnKind::Elapsed => "耗时",
       ColumnKind::Value => "标准值",
       ColumnKind::ResValue => "结果值",
       ColumnKind::Type => "类型",
@@ -259,22 +314,38 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
       ColumnKind::Index => text(row_index + 1).into(),
       ColumnKind::Label => text(&format!("{}", app.tag)).into(),
       ColumnKind::State => row.state.to_container(15).into(),
-      ColumnKind::Value => text(&row.value).into(),
-      ColumnKind::ResValue => text(&row.res_value).into(),
-      ColumnKind::Type => text(&app.r#type).into(),
+      ColumnKind::Elapsed => match row.state {
+        super::DataState::Ready | super::DataState::Skipped => text("").into(),
+        super::DataState::Aborted if row.time.elapsed() == 0.0 => text("").into(),
+        super::DataState::Running => text(format!("{:.1}s", row.time.running())).into(),
+        _ => text(format!("{:.2}s", row.time.elapsed())).into(),
+      },
+      ColumnKind::Value => text(app.limits.to_string()).into(),
+      ColumnKind::ResValue => match &row.violation {
+        Some(violation) => column![
+          text(app.limits.format_value(&row.res_value)),
+          text(violation).size(12).style(crate::config::theme::Text::Error),
+        ]
+        .into(),
+        None => text(app.limits.format_value(&row.res_value)).into(),
+      },
+      ColumnKind::Type => text(app.r#type.to_string()).into(),
       ColumnKind::Priority => text(app.priority.to_string()).into(),
       ColumnKind::IsCheck => text(if app.is_check { "是" } else { "否" }).into(),
       ColumnKind::IsRepeat => text(if app.is_repeat { "是" } else { "否" }).into(),
       ColumnKind::IsWait => text(if app.is_wait { "是" } else { "否" }).into(),
       ColumnKind::Timeout => text(&app.timeout.to_string()).into(),
-      ColumnKind::Count => text(&app.count.to_string()).into(),
+      ColumnKind::Count => match row.attempts.len() {
+        0 => text(&app.count.to_string()).into(),
+        n => {
+          let failed = row.attempts.iter().filter(|x| x.state != super::DataState::Success).count();
+          text(format!("{n}/{} (失败{failed})", app.attempts())).into()
+        }
+      },
     };
 
-    container(content)
-      .width(Length::Fill)
-      .height(32)
-      .center_x()
-      .center_y()
+    mouse_area(container(content).width(Length::Fill).height(32).center_x().center_y())
+      .on_press(Message::Select(row_index))
       .into()
   }
 
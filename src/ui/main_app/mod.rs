@@ -1,6 +1,6 @@
 use iced::{
   multi_window::{self, Application},
-  widget::{column, horizontal_space, row, text},
+  widget::{column, horizontal_space, row, text, text_input},
   window, Alignment, Command, Length, Size, Subscription,
 };
 use std::collections::HashMap;
@@ -11,7 +11,7 @@ use super::{
     helpers::{button2, text_with_logo},
     layout,
   },
-  log_app, login_app, setting_app,
+  history_app, log_app, login_app, setting_app, spc_app, stats_app,
 };
 use crate::{
   config::{
@@ -28,6 +28,7 @@ use crate::{
     r#type::user::UserCfg,
   },
   event,
+  plugins::control::Control,
   res::PanicAny,
   ui::components::{empty, Element},
 };
@@ -41,11 +42,16 @@ pub enum Message {
   SaveConfigResult(Result<Config, String>),
   FontLoaded(Result<(), iced::font::Error>),
   LogEvent(log_app::Message),
+  HistoryEvent(history_app::Message),
+  StatsEvent(stats_app::Message),
+  SpcEvent(spc_app::Message),
   LoginEvent(login_app::Message),
   AutotestEvent(autotest::Message),
   SettingEvent(setting_app::Message),
   Login(View),
   LogPressed,
+  HistoryPressed,
+  StatsPressed,
   MainPressed,
   Ignore,
 }
@@ -53,6 +59,9 @@ pub enum Message {
 pub enum View {
   Main,
   Log,
+  History,
+  Stats,
+  Spc,
   Setting,
   Login,
 }
@@ -89,6 +98,9 @@ pub struct App {
   pub view: View,
   pub flag: Config,
   pub log_app: log_app::App,
+  pub history_app: history_app::App,
+  pub stats_app: stats_app::App,
+  pub spc_app: spc_app::App,
   pub login_app: login_app::App,
   pub setting_app: setting_app::App,
   pub autotest: autotest::App,
@@ -101,7 +113,7 @@ impl App {
   pub fn launch() -> crate::Result<()> {
     // load configuration
     let mut config = Config::default();
-    config.init_base_conf();
+    config.init_base_conf()?;
     let (sub, _guards) = config.log.get_subscriber(config.log.level);
     config.log.init(sub).panic("Init log");
     Self::run(Self::setting_global(config))?;
@@ -134,7 +146,12 @@ impl App {
   /// 更新
   pub fn update_cfg(&mut self, flag: Config) {
     self.setting_app.flag = flag.clone();
+    self.setting_app.sequence.reset(&flag.apps);
     self.log_app.flag = flag.user_cfg.clone();
+    self.history_app.flag = flag.clone();
+    self.stats_app.flag = flag.clone();
+    self.spc_app.flag = flag.clone();
+    self.autotest.flag = flag.clone();
     self.flag = flag;
   }
 
@@ -154,6 +171,21 @@ impl App {
           self.log_app.id = window::Id::MAIN;
           e
         }
+        x if x == self.history_app.id => {
+          let e = self.history_app.close().map(Message::HistoryEvent);
+          self.history_app.id = window::Id::MAIN;
+          e
+        }
+        x if x == self.stats_app.id => {
+          let e = self.stats_app.close().map(Message::StatsEvent);
+          self.stats_app.id = window::Id::MAIN;
+          e
+        }
+        x if x == self.spc_app.id => {
+          let e = self.spc_app.close().map(Message::SpcEvent);
+          self.spc_app.id = window::Id::MAIN;
+          e
+        }
         x if x == self.login_app.id => {
           let e = self.login_app.close().map(Message::LoginEvent);
           self.login_app.id = window::Id::MAIN;
@@ -186,6 +218,21 @@ impl App {
         self.window_flags.insert(self.log_app.id, view);
         e
       }
+      View::History => {
+        let e = self.history_app.create().map(Message::HistoryEvent);
+        self.window_flags.insert(self.history_app.id, view);
+        e
+      }
+      View::Stats => {
+        let e = self.stats_app.create().map(Message::StatsEvent);
+        self.window_flags.insert(self.stats_app.id, view);
+        e
+      }
+      View::Spc => {
+        let e = self.spc_app.create().map(Message::SpcEvent);
+        self.window_flags.insert(self.spc_app.id, view);
+        e
+      }
       View::Setting => {
         let e = self.setting_app.create().map(Message::SettingEvent);
         self.window_flags.insert(self.setting_app.id, view);
@@ -199,6 +246,13 @@ impl App {
       }
     }
   }
+
+  /// 打开步骤的SPC控制图, `station`为空时不限工站
+  pub fn open_spc(&mut self, tag: String, station: String) -> Command<Message> {
+    let e = self.spc_app.open(tag, station).map(Message::SpcEvent);
+    self.window_flags.insert(self.spc_app.id, View::Spc);
+    e
+  }
 }
 /// TODO: allow the user to customize their application icon
 pub fn application_icon() -> Option<iced::window::Icon> {
@@ -217,6 +271,9 @@ impl multi_window::Application for App {
       focused_id: window::Id::MAIN,
       view: View::Main,
       log_app: log_app::App::new(flags.user_cfg.clone()),
+      history_app: history_app::App::new(flags.clone()),
+      stats_app: stats_app::App::new(flags.clone()),
+      spc_app: spc_app::App::new(flags.clone()),
       login_app: login_app::App::new(UserCfg {
         height: 300.0,
         width: 500.0,
@@ -229,7 +286,18 @@ impl multi_window::Application for App {
       run_time: Instant::now(),
       flag: flags,
     };
-    (app, Command::batch([font::load().map(Message::FontLoaded)]))
+    let focus_sn = text_input::focus(app.autotest.sn_id.clone());
+    let load = app.autotest.reload();
+    let flush = app.autotest.flush();
+    (
+      app,
+      Command::batch([
+        font::load().map(Message::FontLoaded),
+        load.map(Message::AutotestEvent),
+        flush.map(Message::AutotestEvent),
+        focus_sn,
+      ]),
+    )
   }
 
   fn title(&self, id: window::Id) -> String {
@@ -241,6 +309,9 @@ impl multi_window::Application for App {
         )
       }
       x if x == self.log_app.id => self.log_app.title(),
+      x if x == self.history_app.id => self.history_app.title(),
+      x if x == self.stats_app.id => self.stats_app.title(),
+      x if x == self.spc_app.id => self.spc_app.title(),
       x if x == self.setting_app.id => self.setting_app.title(),
       x if x == self.login_app.id => self.login_app.title(),
       _ => "Unknown Window Id".to_string(),
@@ -256,6 +327,9 @@ impl multi_window::Application for App {
       x if x == self.id => self.main_view(),
       x if x == self.login_app.id => self.login_app.view().map(Message::LoginEvent),
       x if x == self.log_app.id => self.log_app.view().map(Message::LogEvent),
+      x if x == self.history_app.id => self.history_app.view().map(Message::HistoryEvent),
+      x if x == self.stats_app.id => self.stats_app.view().map(Message::StatsEvent),
+      x if x == self.spc_app.id => self.spc_app.view().map(Message::SpcEvent),
       x if x == self.setting_app.id => self.setting_app.view().map(Message::SettingEvent),
       _ => empty::not_found().into(),
     }
@@ -263,9 +337,15 @@ impl multi_window::Application for App {
 
   fn update(&mut self, message: Message) -> Command<Message> {
     match message {
+      Message::AutotestEvent(autotest::Message::Spc(tag)) => {
+        let station = self.flag.station_name().to_string();
+        return self.open_spc(tag, station);
+      }
       Message::AutotestEvent(e) => return self.autotest.update(e).map(Message::AutotestEvent),
       Message::MainPressed => self.view = View::Main,
       Message::LogPressed => return self.create(View::Log),
+      Message::HistoryPressed => return self.create(View::History),
+      Message::StatsPressed => return self.create(View::Stats),
       Message::Login(view) => {
         self.login_app.view = view;
         return self.create(View::Login);
@@ -281,10 +361,17 @@ impl multi_window::Application for App {
       Message::FontLoaded(result) => log::debug(format!("Successful to load font {:?}", result), Tag::LoadSetting),
       Message::SaveConfig => {
         let flag = self.setting_app.flag.clone();
+        if let Err(e) = self.setting_app.sequence.validate(&flag.apps) {
+          a_task!(async move {
+            log::a_error_box("配置", format!("测试步骤校验失败: {e}"), Tag::DatabaseOffline).await
+          });
+          return Command::none();
+        }
+        let base = self.setting_app.sequence.base().to_vec();
         return Command::perform(
           async move {
             let pool = DB_SQLITE.read().await.pool();
-            save_all_data(&pool, flag).await
+            save_all_data(&pool, flag, base).await
           },
           Message::SaveConfigResult,
         );
@@ -306,6 +393,7 @@ impl multi_window::Application for App {
             log::info(format!("配置明细： {cfg:#?}"), Tag::DatabaseOffline);
             log::a_info_box("配置", "成功加载配置", Tag::DatabaseOffline).await
           });
+          return self.autotest.reload().map(Message::AutotestEvent);
         }
         Err(e) => {
           a_task!(async move {
@@ -321,7 +409,8 @@ impl multi_window::Application for App {
             |_| Message::Ignore,
           );
           let task = self.close(self.setting_app.id);
-          return Command::batch([task, task2]);
+          let task3 = self.autotest.reload().map(Message::AutotestEvent);
+          return Command::batch([task, task2, task3]);
         }
         Err(e) => {
           a_task!(async move {
@@ -342,7 +431,23 @@ impl multi_window::Application for App {
           _ => return self.close(id),
         },
         event::Event::CloseRequested => {}
-        event::Event::Delete => {}
+        // 运行中 Delete 中止测试
+        event::Event::Delete => {
+          if self.autotest.running {
+            return self.update(Message::AutotestEvent(autotest::Message::Control(Control::Abort)));
+          }
+        }
+        event::Event::Start => return self.update(Message::AutotestEvent(autotest::Message::SnSubmit)),
+        event::Event::Pause => {
+          let control = match self.autotest.control.get() {
+            Control::Pause => Control::Run,
+            _ => Control::Pause,
+          };
+          return self.update(Message::AutotestEvent(autotest::Message::Control(control)));
+        }
+        event::Event::Step => {
+          return self.update(Message::AutotestEvent(autotest::Message::Control(Control::Step)))
+        }
         event::Event::Save => return self.update(Message::SaveConfig),
         event::Event::Load => return self.update(Message::LoadConfig),
         event::Event::Focused(id) => {
@@ -353,10 +458,25 @@ impl multi_window::Application for App {
         }
       },
       Message::LogEvent(e) => return self.log_app.update(e).map(Message::LogEvent),
+      Message::HistoryEvent(history_app::Message::Spc(tag, station)) => return self.open_spc(tag, station),
+      Message::HistoryEvent(e) => return self.history_app.update(e).map(Message::HistoryEvent),
+      Message::StatsEvent(stats_app::Message::Select(tag)) => {
+        let filter = self.stats_app.history_filter(tag);
+        let e = self.history_app.filter_by(filter).map(Message::HistoryEvent);
+        self.window_flags.insert(self.history_app.id, View::History);
+        return e;
+      }
+      Message::StatsEvent(e) => return self.stats_app.update(e).map(Message::StatsEvent),
+      Message::SpcEvent(e) => return self.spc_app.update(e).map(Message::SpcEvent),
       Message::SettingEvent(e) => {
         return match e {
           setting_app::Message::Save => self.update(Message::SaveConfig),
-          setting_app::Message::ReLoad => self.update(Message::LoadConfig),
+          setting_app::Message::ReLoad => {
+            // 先放弃未保存的步骤编辑, 加载完成后再以数据库中的步骤重置
+            self.setting_app.flag.apps = self.flag.apps.clone();
+            self.setting_app.sequence.reset(&self.flag.apps);
+            self.update(Message::LoadConfig)
+          }
           _ => self.setting_app.update(e).map(Message::SettingEvent),
         };
       }
@@ -369,7 +489,11 @@ impl multi_window::Application for App {
     iced::Subscription::batch([
       event::events().map(Message::Event),
       self.log_app.subscription().map(Message::LogEvent),
+      self.history_app.subscription().map(Message::HistoryEvent),
+      self.stats_app.subscription().map(Message::StatsEvent),
+      self.spc_app.subscription().map(Message::SpcEvent),
       self.setting_app.subscription().map(Message::SettingEvent),
+      self.autotest.subscription().map(Message::AutotestEvent),
     ])
   }
 }
@@ -378,7 +502,9 @@ impl App {
     let header_tools = row![
       text(format!("运行时间: {}/S", self.run_time.elapsed().as_secs())),
       horizontal_space(),
-      text("当前工站: IFT")
+      self.autotest.sn_bar().map(Message::AutotestEvent),
+      self.autotest.outbox_view().map(Message::AutotestEvent),
+      text(format!("当前工站: {}", self.flag.station_name()))
     ]
     .align_items(Alignment::Center)
     .spacing(10)
@@ -404,6 +530,14 @@ impl App {
           .width(Length::Fill)
           .style(self.view.get_theme(&View::Log))
           .on_press(Message::LogPressed),
+        button2(text_with_logo(icon::time(), "记录"))
+          .width(Length::Fill)
+          .style(self.view.get_theme(&View::History))
+          .on_press(Message::HistoryPressed),
+        button2(text_with_logo(icon::layout(), "统计"))
+          .width(Length::Fill)
+          .style(self.view.get_theme(&View::Stats))
+          .on_press(Message::StatsPressed),
       ]
       .align_items(Alignment::Center)
       .width(100),
@@ -1,9 +1,12 @@
 //! Self contained data stuff
 
 pub mod components;
+pub mod history_app;
 #[path = "log_app.rs"]
 pub mod log_app;
 #[path = "login_app.rs"]
 pub mod login_app;
 pub mod main_app;
 pub mod setting_app;
+pub mod spc_app;
+pub mod stats_app;
@@ -1,5 +1,8 @@
 use crate::{
   config::{
+    mes::MesCfg,
+    report::ReportCfg,
+    runner::RunnerCfg,
     theme::{self, Themes},
     Config,
   },
@@ -10,10 +13,18 @@ use crate::{
     Column, Element, Row,
   },
 };
-use iced::widget::{column, container, pick_list, row};
+use iced::widget::{checkbox, column, container, pick_list, row, text, text_input};
+use serde_json::Value;
 
-pub fn view(flag: &Config) -> Column<'_, super::Message> {
-  column![apps(&flag)].spacing(8)
+pub fn view<'a>(flag: &'a Config, headers_input: &'a str) -> Column<'a, super::Message> {
+  column![
+    apps(&flag),
+    runner(&flag.runner),
+    product(&flag),
+    mes(&flag.mes, headers_input),
+    report(&flag.report)
+  ]
+  .spacing(8)
 }
 
 /// 应用配置
@@ -34,3 +45,108 @@ pub fn themes(cfg: &UserCfg) -> Element<'_, super::Message> {
     .style(theme::Container::Frame)
     .into()
 }
+
+/// 产品及SN校验
+pub fn product(flag: &Config) -> Element<'_, super::Message> {
+  let names: Vec<String> = flag.products.iter().map(|x| x.name.clone()).collect();
+  let current = flag.product();
+  let settings = row![
+    pick_list(names, current.map(|x| x.name.clone()), super::Message::SetProduct),
+    "SN规则：",
+    text_input("^[0-9A-Z-]+$", current.map(|x| x.sn_pattern.as_str()).unwrap_or_default())
+      .width(320)
+      .on_input(super::Message::SetSnPattern)
+  ]
+  .spacing(8)
+  .align_items(iced::Alignment::Center);
+  control(container(text_with_logo(icon::appstore(), "产品")), settings)
+    .style(theme::Container::Frame)
+    .into()
+}
+
+/// 测试执行
+pub fn runner(cfg: &RunnerCfg) -> Element<'_, super::Message> {
+  let settings = row![
+    "最大并行数：",
+    text_input("1", &cfg.max_parallel.to_string())
+      .width(80)
+      .on_input(super::Message::SetMaxParallel)
+  ]
+  .spacing(8)
+  .align_items(iced::Alignment::Center);
+  control(container(text_with_logo(icon::control(), "测试执行")), settings)
+    .style(theme::Container::Frame)
+    .into()
+}
+
+/// 请求头输入框内容, 与配置不一致时(如重新加载后)显示配置
+pub fn headers_text(cfg: &MesCfg, input: &str) -> String {
+  match serde_json::from_str::<Value>(input) {
+    Ok(v) if v == cfg.headers => input.to_string(),
+    _ if input.is_empty() => cfg.headers.to_string(),
+    Ok(_) => cfg.headers.to_string(),
+    Err(_) => input.to_string(),
+  }
+}
+
+/// MES 上传
+pub fn mes<'a>(cfg: &'a MesCfg, headers_input: &'a str) -> Element<'a, super::Message> {
+  let headers = headers_text(cfg, headers_input);
+  let mut settings = column![
+    row![
+      "请求头：",
+      text_input(r#"{"X-Station": "IFT"}"#, &headers).on_input(super::Message::SetMesHeaders)
+    ]
+    .spacing(8)
+    .align_items(iced::Alignment::Center),
+    row![
+      "Token：",
+      text_input("Bearer Token", &cfg.token)
+        .secure(true)
+        .on_input(super::Message::SetMesToken),
+      "超时(秒)：",
+      text_input("10", &cfg.timeout.to_string())
+        .width(80)
+        .on_input(super::Message::SetMesTimeout),
+      "重试间隔(秒)：",
+      text_input("30", &cfg.retry_interval.to_string())
+        .width(80)
+        .on_input(super::Message::SetMesRetryInterval),
+      checkbox("上传步骤结果", cfg.upload_steps).on_toggle(super::Message::SetMesUploadSteps),
+    ]
+    .spacing(8)
+    .align_items(iced::Alignment::Center),
+  ]
+  .spacing(8);
+  if serde_json::from_str::<serde_json::Map<String, Value>>(&headers).is_err() {
+    settings = settings.push(text("请求头需为JSON对象").style(theme::Text::Error));
+  }
+  control(container(text_with_logo(icon::upload_cloud(), "MES上传")), settings)
+    .style(theme::Container::Frame)
+    .into()
+}
+
+/// 测试报告
+pub fn report(cfg: &ReportCfg) -> Element<'_, super::Message> {
+  let settings = column![
+    row![
+      "目录：",
+      text_input("#origin#/reports", &cfg.folder).on_input(super::Message::SetReportFolder),
+      "文件名：",
+      text_input("#sn#_#station#_#timestamp#", &cfg.fname).on_input(super::Message::SetReportFname),
+    ]
+    .spacing(8)
+    .align_items(iced::Alignment::Center),
+    row![
+      checkbox("HTML", cfg.html).on_toggle(super::Message::SetReportHtml),
+      checkbox("JUnit XML", cfg.junit).on_toggle(super::Message::SetReportJunit),
+      checkbox("CSV", cfg.csv).on_toggle(super::Message::SetReportCsv),
+    ]
+    .spacing(8)
+    .align_items(iced::Alignment::Center),
+  ]
+  .spacing(8);
+  control(container(text_with_logo(icon::print(), "测试报告")), settings)
+    .style(theme::Container::Frame)
+    .into()
+}
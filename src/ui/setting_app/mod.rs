@@ -4,6 +4,8 @@ use iced::{
   Alignment, Command, Length, Size,
 };
 mod base;
+pub mod sequence;
+mod station;
 mod user;
 use super::{
   components::{helpers::text_with_logo, layout, Row},
@@ -11,6 +13,7 @@ use super::{
 };
 use crate::{
   config::{
+    station::Station,
     theme::{self, Themes},
     Config,
   },
@@ -25,6 +28,8 @@ pub enum View {
   #[default]
   Main,
   User,
+  Station,
+  Sequence,
 }
 impl View {
   /// 校验主题
@@ -42,6 +47,12 @@ pub struct App {
   pub view: View,
   pub flag: Config,
   pub id: window::Id,
+  /// 工站步骤输入框
+  pub steps_input: String,
+  /// 测试步骤编辑器
+  pub sequence: sequence::Editor,
+  /// MES请求头输入框
+  pub headers_input: String,
 }
 impl Default for App {
   fn default() -> Self {
@@ -49,6 +60,9 @@ impl Default for App {
       id: window::Id::MAIN,
       view: View::Main,
       flag: Config::default(),
+      steps_input: String::new(),
+      sequence: sequence::Editor::default(),
+      headers_input: String::new(),
     }
   }
 }
@@ -57,9 +71,37 @@ impl Default for App {
 pub enum Message {
   UserPressed,
   MainViewPressed,
+  StationPressed,
+  SequencePressed,
+  /// 测试步骤编辑
+  Sequence(sequence::Message),
   SetUserName(String),
   SetUserPasswd(String),
   SetTheme(Themes),
+  SetMaxParallel(String),
+  /// 切换当前产品
+  SetProduct(String),
+  /// 当前产品的SN校验正则
+  SetSnPattern(String),
+  /// 切换当前工站
+  SetStation(String),
+  SetStationSteps(String),
+  SetStationSnPattern(String),
+  SetStationUploadUrl(String),
+  SetStationPreHook(String),
+  SetStationPostHook(String),
+  /// MES请求头, JSON对象
+  SetMesHeaders(String),
+  SetMesToken(String),
+  SetMesTimeout(String),
+  SetMesRetryInterval(String),
+  SetMesUploadSteps(bool),
+  /// 报告目录及文件名, 支持`#key#`变量
+  SetReportFolder(String),
+  SetReportFname(String),
+  SetReportHtml(bool),
+  SetReportJunit(bool),
+  SetReportCsv(bool),
   Save,
   ReLoad,
 }
@@ -67,8 +109,10 @@ pub enum Message {
 pub fn view(app: &App) -> Element<'_, Message> {
   let cont = layout::content(
     match app.view {
-      View::Main => base::view(&app.flag),
+      View::Main => base::view(&app.flag, &app.headers_input),
       View::User => user::view(&app.flag),
+      View::Station => station::view(&app.flag, &app.steps_input),
+      View::Sequence => column![Element::from(app.sequence.view(&app.flag.apps)).map(Message::Sequence)],
     }
     .push(bts()),
   );
@@ -81,7 +125,15 @@ pub fn view(app: &App) -> Element<'_, Message> {
       button2(text_with_logo(icon::user(), "用户"))
         .width(Length::Fill)
         .style(app.view.get_theme(&View::User))
-        .on_press(Message::UserPressed)
+        .on_press(Message::UserPressed),
+      button2(text_with_logo(icon::layout(), "工站"))
+        .width(Length::Fill)
+        .style(app.view.get_theme(&View::Station))
+        .on_press(Message::StationPressed),
+      button2(text_with_logo(icon::appstore(), "步骤"))
+        .width(Length::Fill)
+        .style(app.view.get_theme(&View::Sequence))
+        .on_press(Message::SequencePressed)
     ]
     .align_items(Alignment::Center)
     .width(100),
@@ -94,14 +146,93 @@ pub fn update(app: &mut App, message: Message) -> Command<Message> {
   match message {
     Message::UserPressed => app.view = View::User,
     Message::MainViewPressed => app.view = View::Main,
+    Message::StationPressed => app.view = View::Station,
+    Message::SequencePressed => app.view = View::Sequence,
+    Message::Sequence(e) => return app.sequence.update(&mut cfg.apps, e).map(Message::Sequence),
     Message::SetUserName(v) => cfg.user.name = v,
     Message::SetUserPasswd(v) => cfg.user.password = v,
     Message::SetTheme(theme) => cfg.user_cfg.theme = theme,
+    Message::SetMaxParallel(v) => {
+      if let Ok(v) = v.parse::<i32>() {
+        cfg.runner.max_parallel = v.max(1);
+      }
+    }
+    Message::SetProduct(name) => {
+      if let Some(product) = cfg.products.iter().find(|x| x.name == name) {
+        cfg.runner.product_id = product.id;
+      }
+    }
+    Message::SetSnPattern(v) => {
+      let id = cfg.runner.product_id;
+      if let Some(product) = cfg.products.iter_mut().find(|x| x.id == id) {
+        product.sn_pattern = v;
+      }
+    }
+    Message::SetStation(name) => {
+      if let Some(station) = cfg.stations.iter().find(|x| x.name == name) {
+        cfg.runner.station_id = station.id;
+      }
+    }
+    Message::SetStationSteps(v) => {
+      if let Some(station) = station_mut(cfg) {
+        station.steps = station::parse_steps(&v);
+      }
+      app.steps_input = v;
+    }
+    Message::SetStationSnPattern(v) => {
+      if let Some(station) = station_mut(cfg) {
+        station.sn_pattern = v;
+      }
+    }
+    Message::SetStationUploadUrl(v) => {
+      if let Some(station) = station_mut(cfg) {
+        station.upload_url = v;
+      }
+    }
+    Message::SetStationPreHook(v) => {
+      if let Some(station) = station_mut(cfg) {
+        station.pre_hook = v;
+      }
+    }
+    Message::SetStationPostHook(v) => {
+      if let Some(station) = station_mut(cfg) {
+        station.post_hook = v;
+      }
+    }
+    Message::SetMesHeaders(v) => {
+      if let Ok(headers) = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&v) {
+        cfg.mes.headers = serde_json::Value::Object(headers);
+      }
+      app.headers_input = v;
+    }
+    Message::SetMesToken(v) => cfg.mes.token = v,
+    Message::SetMesTimeout(v) => {
+      if let Ok(v) = v.parse::<i32>() {
+        cfg.mes.timeout = v.max(1);
+      }
+    }
+    Message::SetMesRetryInterval(v) => {
+      if let Ok(v) = v.parse::<i32>() {
+        cfg.mes.retry_interval = v.max(5);
+      }
+    }
+    Message::SetMesUploadSteps(v) => cfg.mes.upload_steps = v,
+    Message::SetReportFolder(v) => cfg.report.folder = v,
+    Message::SetReportFname(v) => cfg.report.fname = v,
+    Message::SetReportHtml(v) => cfg.report.html = v,
+    Message::SetReportJunit(v) => cfg.report.junit = v,
+    Message::SetReportCsv(v) => cfg.report.csv = v,
     Message::Save | Message::ReLoad => (),
   }
   Command::none()
 }
 
+/// 当前工站配置
+fn station_mut(cfg: &mut Config) -> Option<&mut Station> {
+  let id = cfg.runner.station_id;
+  cfg.stations.iter_mut().find(|x| x.id == id)
+}
+
 /// 提交
 pub fn bts() -> Element<'static, Message> {
   column![
@@ -132,6 +263,7 @@ impl AppWindow for App {
 
   fn new(flag: Self::Flag) -> Self {
     let mut slf = Self::default();
+    slf.sequence = sequence::Editor::new(&flag.apps);
     slf.flag = flag;
     slf
   }
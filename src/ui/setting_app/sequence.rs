@@ -0,0 +1,641 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use iced::{
+  widget::{checkbox, column, container, pick_list, row, scrollable, text, text_input, Column, Space},
+  Alignment, Command, Length,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+  config::theme,
+  data::{icon, ORIGIN},
+  db::{
+    r#type::app::ExtendApp,
+  },
+  plugins::{
+    exchange::{self, Diff, Mode},
+    schedule::schedule,
+    step::StepKind,
+  },
+  ui::components::{
+    helpers::{button2, text_with_logo},
+    Element,
+  },
+};
+
+/// 步骤类型数量, 见`StepKind::code`
+const KIND_COUNT: i32 = 7;
+/// 默认导入导出文件
+const DEFAULT_PATH: &str = "sequence.toml";
+
+#[derive(Debug, Clone)]
+pub enum Message {
+  Select(usize),
+  Add,
+  Duplicate,
+  Delete,
+  Up,
+  Down,
+  /// 列表中启用/禁用
+  Toggle(usize, bool),
+  Input(Field, String),
+  Check(Flag, bool),
+  SetKind(StepKind),
+  /// 导入导出文件路径, 格式由扩展名决定
+  Path(String),
+  SetMode(Mode),
+  Export,
+  Exported(Result<(), String>),
+  /// 读取文件并预览差异
+  Preview,
+  Previewed(Result<Vec<ExtendApp>, String>),
+  Import,
+  CancelImport,
+}
+
+/// 文本编辑的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Field {
+  Tag,
+  Label,
+  Priority,
+  Timeout,
+  Count,
+  Cmd,
+  Cwd,
+  ResUrl,
+  Filter,
+  Limits,
+  DependsOn,
+  Retry,
+  Capture,
+  Condition,
+  Params,
+}
+impl Field {
+  pub const ALL: [Field; 15] = [
+    Field::Tag,
+    Field::Label,
+    Field::Priority,
+    Field::Timeout,
+    Field::Count,
+    Field::Cmd,
+    Field::Cwd,
+    Field::ResUrl,
+    Field::Filter,
+    Field::Limits,
+    Field::DependsOn,
+    Field::Retry,
+    Field::Capture,
+    Field::Condition,
+    Field::Params,
+  ];
+
+  fn label(&self) -> &'static str {
+    match self {
+      Field::Tag => "标签：",
+      Field::Label => "名称：",
+      Field::Priority => "优先级：",
+      Field::Timeout => "超时(秒)：",
+      Field::Count => "次数：",
+      Field::Cmd => "命令：",
+      Field::Cwd => "工作目录：",
+      Field::ResUrl => "结果地址：",
+      Field::Filter => "结果提取：",
+      Field::Limits => "限值：",
+      Field::DependsOn => "前置步骤：",
+      Field::Retry => "重试策略：",
+      Field::Capture => "保存变量：",
+      Field::Condition => "执行条件：",
+      Field::Params => "类型参数：",
+    }
+  }
+
+  fn placeholder(&self) -> &'static str {
+    match self {
+      Field::Tag => "唯一标签",
+      Field::Label => "显示名称",
+      Field::Priority => "0",
+      Field::Timeout => "0 不限制",
+      Field::Count => "1",
+      Field::Cmd => r#"["python", "test.py"]"#,
+      Field::Cwd => "为空使用当前目录",
+      Field::ResUrl => "",
+      Field::Filter => r#"["regex:(?P<value>\\d+)"]"#,
+      Field::Limits => r#"{"kind": {"type": "none"}}"#,
+      Field::DependsOn => "步骤标签, 逗号分隔",
+      Field::Retry => r#"{"mode": "untilPass"}"#,
+      Field::Capture => "变量名, 后续步骤以 #name# 引用",
+      Field::Condition => r#"{"when": {"type": "none"}, "always": false}"#,
+      Field::Params => r#"{"kind": "process"}"#,
+    }
+  }
+
+  /// 字段的编辑文本
+  fn get(&self, app: &ExtendApp) -> String {
+    match self {
+      Field::Tag => app.tag.clone(),
+      Field::Label => app.label.clone(),
+      Field::Priority => app.priority.to_string(),
+      Field::Timeout => app.timeout.to_string(),
+      Field::Count => app.count.to_string(),
+      Field::Cmd => json(&app.cmd),
+      Field::Cwd => app.cwd.clone().unwrap_or_default(),
+      Field::ResUrl => app.res_url.clone().unwrap_or_default(),
+      Field::Filter => json(&app.filter),
+      Field::Limits => json(&app.limits),
+      Field::DependsOn => app.depends_on.join(", "),
+      Field::Retry => json(&app.retry),
+      Field::Capture => app.capture.clone().unwrap_or_default(),
+      Field::Condition => json(&app.condition),
+      Field::Params => json(&app.r#type),
+    }
+  }
+
+  /// 校验并写入字段
+  fn set(&self, app: &mut ExtendApp, v: &str) -> Result<(), String> {
+    let optional = |v: &str| Some(v.trim().to_string()).filter(|x| !x.is_empty());
+    match self {
+      Field::Tag => {
+        let v = v.trim();
+        if v.is_empty() || v.contains([',', '，', '#']) || v.contains(char::is_whitespace) {
+          return Err("标签不能为空, 且不能包含空白、逗号或#".to_string());
+        }
+        app.tag = v.to_string();
+      }
+      Field::Label => app.label = v.to_string(),
+      Field::Priority => app.priority = v.trim().parse().map_err(|_| "需要整数".to_string())?,
+      Field::Timeout => app.timeout = v.trim().parse().map_err(|_| "需要整数, 0 不限制".to_string())?,
+      Field::Count => {
+        app.count = v
+          .trim()
+          .parse()
+          .ok()
+          .filter(|x| *x >= 1)
+          .ok_or("需要大于0的整数".to_string())?
+      }
+      Field::Cmd => app.cmd = parse_json(v)?,
+      Field::Cwd => app.cwd = optional(v),
+      Field::ResUrl => app.res_url = optional(v),
+      Field::Filter => app.filter = parse_json(v)?,
+      Field::Limits => app.limits = parse_json(v)?,
+      Field::DependsOn => {
+        app.depends_on = v
+          .split([',', '，'])
+          .map(str::trim)
+          .filter(|x| !x.is_empty())
+          .map(String::from)
+          .collect()
+      }
+      Field::Retry => app.retry = parse_json(v)?,
+      Field::Capture => app.capture = optional(v),
+      Field::Condition => app.condition = parse_json(v)?,
+      Field::Params => app.r#type = parse_json(v)?,
+    }
+    Ok(())
+  }
+}
+
+/// 勾选编辑的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+  Enable,
+  IsCheck,
+  IsRepeat,
+  IsWait,
+}
+impl Flag {
+  const ALL: [Flag; 4] = [Flag::Enable, Flag::IsCheck, Flag::IsRepeat, Flag::IsWait];
+
+  fn label(&self) -> &'static str {
+    match self {
+      Flag::Enable => "启用",
+      Flag::IsCheck => "判定结果",
+      Flag::IsRepeat => "重复执行",
+      Flag::IsWait => "单独执行",
+    }
+  }
+
+  fn get(&self, app: &ExtendApp) -> bool {
+    match self {
+      Flag::Enable => app.enable,
+      Flag::IsCheck => app.is_check,
+      Flag::IsRepeat => app.is_repeat,
+      Flag::IsWait => app.is_wait,
+    }
+  }
+
+  fn set(&self, app: &mut ExtendApp, v: bool) {
+    match self {
+      Flag::Enable => app.enable = v,
+      Flag::IsCheck => app.is_check = v,
+      Flag::IsRepeat => app.is_repeat = v,
+      Flag::IsWait => app.is_wait = v,
+    }
+  }
+}
+
+fn json<T: Serialize>(v: &T) -> String {
+  serde_json::to_string(v).unwrap_or_default()
+}
+
+fn parse_json<T: DeserializeOwned>(v: &str) -> Result<T, String> {
+  serde_json::from_str(v).map_err(|e| format!("JSON格式错误: {e}"))
+}
+
+/// 步骤编辑器
+#[derive(Debug, Default)]
+pub struct Editor {
+  pub selected: Option<usize>,
+  /// 编辑中的文本, 校验通过后写入步骤
+  inputs: HashMap<Field, String>,
+  /// 未通过校验的字段
+  errors: HashMap<Field, String>,
+  path: String,
+  mode: Mode,
+  /// 待导入的步骤及差异
+  preview: Option<(Vec<ExtendApp>, Diff)>,
+  /// 导入导出结果
+  notice: Option<Result<String, String>>,
+  /// 编辑前的步骤, 保存配置时只写入相对它的改动
+  base: Vec<ExtendApp>,
+}
+
+impl Editor {
+  pub fn new(apps: &[ExtendApp]) -> Self {
+    Self {
+      base: apps.to_vec(),
+      ..Default::default()
+    }
+  }
+
+  /// 加载或保存配置后放弃未保存的编辑, 保留导入导出的路径和方式
+  pub fn reset(&mut self, apps: &[ExtendApp]) {
+    *self = Self {
+      path: std::mem::take(&mut self.path),
+      mode: self.mode,
+      ..Self::new(apps)
+    };
+  }
+
+  /// 编辑前的步骤
+  pub fn base(&self) -> &[ExtendApp] {
+    &self.base
+  }
+
+  fn select(&mut self, index: Option<usize>, apps: &[ExtendApp]) {
+    self.selected = index.filter(|i| *i < apps.len());
+    self.errors.clear();
+    self.inputs = match self.selected {
+      Some(i) => Field::ALL.iter().map(|x| (*x, x.get(&apps[i]))).collect(),
+      None => HashMap::new(),
+    };
+  }
+
+  /// 当前步骤有未通过校验的输入时提示并返回`true`, 切换选中会丢弃这些输入
+  fn blocked(&mut self) -> bool {
+    match self.errors.iter().next() {
+      Some((field, e)) => {
+        self.notice = Some(Err(format!("请先修正当前步骤的{}{e}", field.label())));
+        true
+      }
+      None => false,
+    }
+  }
+
+  /// 校验全部步骤: 字段格式、标签重复、依赖及执行条件
+  pub fn validate(&self, apps: &[ExtendApp]) -> Result<(), String> {
+    if let Some((field, e)) = self.errors.iter().next() {
+      return Err(format!("{}{e}", field.label()));
+    }
+    if let Some(app) = apps.iter().find(|x| x.tag.trim().is_empty()) {
+      return Err(format!("步骤标签不能为空: {}", app.label));
+    }
+    schedule(apps.to_vec()).map(|_| ()).map_err(|e| e.to_string())
+  }
+
+  fn path(&self) -> String {
+    match self.path.trim() {
+      "" => DEFAULT_PATH.to_string(),
+      path => path.to_string(),
+    }
+  }
+
+  /// 界面中的相对路径基于程序目录
+  fn file(&self) -> PathBuf {
+    ORIGIN.join(self.path())
+  }
+
+  pub fn update(&mut self, apps: &mut Vec<ExtendApp>, message: Message) -> Command<Message> {
+    match message {
+      Message::Path(v) => self.path = v,
+      Message::SetMode(mode) => {
+        self.mode = mode;
+        if let Some((incoming, diff)) = &mut self.preview {
+          if let Ok(v) = exchange::diff(apps, incoming, mode) {
+            *diff = v;
+          }
+        }
+      }
+      Message::Export => {
+        self.notice = None;
+        return Command::perform(exchange::export_file(apps.clone(), self.file()), |res| {
+          Message::Exported(res.map_err(|e| e.to_string()))
+        });
+      }
+      Message::Exported(res) => self.notice = Some(res.map(|_| format!("已导出: {}", self.file().display()))),
+      Message::Preview => {
+        self.notice = None;
+        return Command::perform(exchange::import_file(self.file()), |res| {
+          Message::Previewed(res.map_err(|e| e.to_string()))
+        });
+      }
+      Message::Previewed(res) => {
+        match res.and_then(|incoming| {
+          let diff = exchange::diff(apps, &incoming, self.mode).map_err(|e| e.to_string())?;
+          Ok((incoming, diff))
+        }) {
+          Ok(preview) => self.preview = Some(preview),
+          Err(e) => self.notice = Some(Err(e)),
+        }
+      }
+      Message::Import => {
+        if self.blocked() {
+          return Command::none();
+        }
+        if let Some((incoming, _)) = self.preview.take() {
+          let count = incoming.len();
+          *apps = exchange::apply(std::mem::take(apps), incoming, self.mode);
+          self.select(None, apps);
+          self.notice = Some(Ok(format!("已{}导入{count}个步骤, 保存后生效", self.mode)));
+        }
+      }
+      Message::CancelImport => self.preview = None,
+      Message::Select(i) => {
+        if self.selected != Some(i) && !self.blocked() {
+          self.select(Some(i), apps);
+        }
+      }
+      Message::Add => {
+        if self.blocked() {
+          return Command::none();
+        }
+        let index = self.selected.map(|i| i + 1).unwrap_or(apps.len());
+        let app = ExtendApp {
+          tag: unique_tag(apps, "step"),
+          enable: true,
+          count: 1,
+          ..Default::default()
+        };
+        apps.insert(index, app);
+        reorder(apps);
+        self.select(Some(index), apps);
+      }
+      Message::Duplicate => {
+        if let Some(i) = self.selected.filter(|_| !self.blocked()) {
+          let app = ExtendApp {
+            id: 0,
+            tag: unique_tag(apps, &apps[i].tag),
+            ..apps[i].clone()
+          };
+          apps.insert(i + 1, app);
+          reorder(apps);
+          self.select(Some(i + 1), apps);
+        }
+      }
+      Message::Delete => {
+        if let Some(i) = self.selected {
+          apps.remove(i);
+          reorder(apps);
+          self.select(None, apps);
+        }
+      }
+      Message::Up => {
+        if let Some(i) = self.selected.filter(|i| *i > 0 && !self.blocked()) {
+          apps.swap(i, i - 1);
+          reorder(apps);
+          self.select(Some(i - 1), apps);
+        }
+      }
+      Message::Down => {
+        if let Some(i) = self.selected.filter(|i| i + 1 < apps.len() && !self.blocked()) {
+          apps.swap(i, i + 1);
+          reorder(apps);
+          self.select(Some(i + 1), apps);
+        }
+      }
+      Message::Toggle(i, v) => {
+        if let Some(app) = apps.get_mut(i) {
+          app.enable = v;
+        }
+      }
+      Message::Input(field, v) => {
+        if let Some(app) = self.selected.and_then(|i| apps.get_mut(i)) {
+          match field.set(app, &v) {
+            Ok(()) => self.errors.remove(&field),
+            Err(e) => self.errors.insert(field, e),
+          };
+          self.inputs.insert(field, v);
+        }
+      }
+      Message::Check(flag, v) => {
+        if let Some(app) = self.selected.and_then(|i| apps.get_mut(i)) {
+          flag.set(app, v);
+        }
+      }
+      Message::SetKind(kind) => {
+        if let Some(app) = self.selected.and_then(|i| apps.get_mut(i)) {
+          if app.r#type.code() != kind.code() {
+            app.r#type = kind;
+            self.inputs.insert(Field::Params, Field::Params.get(app));
+            self.errors.remove(&Field::Params);
+          }
+        }
+      }
+    }
+    Command::none()
+  }
+
+  pub fn view<'a>(&'a self, apps: &'a [ExtendApp]) -> Column<'a, Message> {
+    let selected = self.selected.filter(|i| *i < apps.len());
+    let on = |enable: bool, message: Message| enable.then_some(message);
+    let tools = row![
+      button2(text_with_logo(icon::plus_circle(), "新增"))
+        .width(90)
+        .on_press(Message::Add),
+      button2(text_with_logo(icon::export(), "复制"))
+        .width(90)
+        .on_press_maybe(on(selected.is_some(), Message::Duplicate)),
+      button2(text_with_logo(icon::delete(), "删除"))
+        .width(90)
+        .style(theme::Button::Delete)
+        .on_press_maybe(on(selected.is_some(), Message::Delete)),
+      button2(text_with_logo(icon::undo(), "上移"))
+        .width(90)
+        .on_press_maybe(on(selected.is_some_and(|i| i > 0), Message::Up)),
+      button2(text_with_logo(icon::redo(), "下移"))
+        .width(90)
+        .on_press_maybe(on(selected.is_some_and(|i| i + 1 < apps.len()), Message::Down)),
+    ]
+    .spacing(8);
+    let list = Column::with_children(apps.iter().enumerate().map(|(i, app)| {
+      row![
+        checkbox("", app.enable).on_toggle(move |v| Message::Toggle(i, v)),
+        button2(
+          row![
+            text(&app.tag).width(Length::FillPortion(2)),
+            text(&app.label).width(Length::FillPortion(3)),
+            text(app.r#type.to_string()).width(Length::FillPortion(1)),
+          ]
+          .spacing(8)
+        )
+        .width(Length::Fill)
+        .style(match selected == Some(i) {
+          true => theme::Button::Pressed,
+          false => theme::Button::Ready,
+        })
+        .on_press(Message::Select(i)),
+      ]
+      .spacing(8)
+      .align_items(Alignment::Center)
+      .into()
+    }))
+    .spacing(4)
+    .padding(8);
+    let list = column![
+      tools,
+      container(scrollable(list).style(theme::Scrollable::Normal))
+        .height(Length::Fill)
+        .style(theme::Container::Frame),
+    ]
+    .spacing(8)
+    .width(Length::FillPortion(2));
+    let form: Element<'_, Message> = match (&self.preview, selected) {
+      (Some((_, diff)), _) => self.preview(diff),
+      (None, Some(i)) => self.form(&apps[i]),
+      (None, None) => container(text("选择或新增步骤进行编辑"))
+        .center_x()
+        .center_y()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into(),
+    };
+    column![
+      self.transfer(),
+      row![list, container(form).width(Length::FillPortion(3))].spacing(8)
+    ]
+    .spacing(8)
+    .height(Length::Fill)
+  }
+
+  /// 导入导出
+  fn transfer(&self) -> Element<'_, Message> {
+    let mut bar = row![
+      text("文件："),
+      text_input(DEFAULT_PATH, &self.path).on_input(Message::Path).width(300),
+      pick_list(Mode::ALL.as_slice(), Some(self.mode), Message::SetMode),
+      button2(text_with_logo(icon::import(), "导入"))
+        .width(90)
+        .on_press(Message::Preview),
+      button2(text_with_logo(icon::export(), "导出"))
+        .width(90)
+        .on_press(Message::Export),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+    match &self.notice {
+      Some(Ok(v)) => bar = bar.push(text(v).style(theme::Text::Success)),
+      Some(Err(e)) => bar = bar.push(text(e).style(theme::Text::Error)),
+      None => {}
+    }
+    bar.into()
+  }
+
+  /// 导入差异预览
+  fn preview<'a>(&'a self, diff: &'a Diff) -> Element<'a, Message> {
+    let mut lines = Column::new().spacing(4).padding(8);
+    if diff.is_empty() {
+      lines = lines.push(text("无变化"));
+    }
+    for tag in &diff.added {
+      lines = lines.push(text(format!("+ 新增 {tag}")).style(theme::Text::Success));
+    }
+    for tag in &diff.removed {
+      lines = lines.push(text(format!("- 删除 {tag}")).style(theme::Text::Error));
+    }
+    for (tag, fields) in &diff.changed {
+      lines = lines.push(text(format!("~ 修改 {tag}: {}", fields.join(", "))).style(theme::Text::Warning));
+    }
+    column![
+      text(format!("导入预览({}): {}", self.mode, self.path())).size(16),
+      container(scrollable(lines).style(theme::Scrollable::Normal))
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .style(theme::Container::Frame),
+      row![
+        button2(text_with_logo(icon::check_circle(), "确认导入"))
+          .width(120)
+          .style(theme::Button::Success)
+          .on_press(Message::Import),
+        button2(text_with_logo(icon::close_circle(), "取消"))
+          .width(120)
+          .on_press(Message::CancelImport),
+      ]
+      .spacing(8),
+    ]
+    .spacing(8)
+    .into()
+  }
+
+  /// 编辑表单
+  fn form<'a>(&'a self, app: &'a ExtendApp) -> Element<'a, Message> {
+    let kinds: Vec<StepKind> = (0..KIND_COUNT).map(StepKind::from_code).collect();
+    let flags = Flag::ALL.into_iter().fold(row![].spacing(16), |row, flag| {
+      row.push(checkbox(flag.label(), flag.get(app)).on_toggle(move |v| Message::Check(flag, v)))
+    });
+    let mut form = column![
+      flags,
+      row![
+        text("类型：").width(100),
+        pick_list(kinds, Some(StepKind::from_code(app.r#type.code())), Message::SetKind)
+      ]
+      .spacing(8)
+      .align_items(Alignment::Center),
+    ]
+    .spacing(8)
+    .padding(8);
+    for field in Field::ALL {
+      let value = self.inputs.get(&field).map(String::as_str).unwrap_or_default();
+      form = form.push(
+        row![
+          text(field.label()).width(100),
+          text_input(field.placeholder(), value).on_input(move |v| Message::Input(field, v))
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center),
+      );
+      if let Some(e) = self.errors.get(&field) {
+        form = form.push(row![Space::with_width(108), text(e).style(theme::Text::Error)]);
+      }
+    }
+    container(scrollable(form).style(theme::Scrollable::Normal))
+      .height(Length::Fill)
+      .style(theme::Container::Frame)
+      .into()
+  }
+}
+
+/// 按列表顺序重写`priority`
+fn reorder(apps: &mut [ExtendApp]) {
+  for (i, app) in apps.iter_mut().enumerate() {
+    app.priority = i as i32;
+  }
+}
+
+/// 不与已有步骤重复的标签
+fn unique_tag(apps: &[ExtendApp], base: &str) -> String {
+  (1..)
+    .map(|i| format!("{base}_{i}"))
+    .find(|tag| apps.iter().all(|x| x.tag != *tag))
+    .unwrap_or_default()
+}
@@ -0,0 +1,79 @@
+use crate::{
+  config::{station::Station, theme, Config},
+  data::icon,
+  ui::components::{
+    helpers::{control, text_with_logo},
+    Column, Element,
+  },
+};
+use iced::widget::{column, container, pick_list, row, text, text_input};
+
+pub fn view<'a>(flag: &'a Config, steps_input: &'a str) -> Column<'a, super::Message> {
+  let mut col = column![current(flag)].spacing(8);
+  if let Some(station) = flag.station() {
+    col = col.push(profile(station, steps_input));
+  }
+  col
+}
+
+/// 步骤输入框内容, 与配置不一致时(如重新加载后)显示配置
+pub fn steps_text(station: &Station, input: &str) -> String {
+  match parse_steps(input) == station.steps {
+    true => input.to_string(),
+    false => station.steps.join(", "),
+  }
+}
+
+/// 以逗号分隔的步骤`tag`
+pub fn parse_steps(input: &str) -> Vec<String> {
+  input
+    .split([',', '，'])
+    .map(str::trim)
+    .filter(|x| !x.is_empty())
+    .map(String::from)
+    .collect()
+}
+
+/// 当前工站
+pub fn current(flag: &Config) -> Element<'_, super::Message> {
+  let names: Vec<String> = flag.stations.iter().map(|x| x.name.clone()).collect();
+  let current = flag.station().map(|x| x.name.clone());
+  let settings = row![pick_list(names, current, super::Message::SetStation), "保存后生效"]
+    .spacing(8)
+    .align_items(iced::Alignment::Center);
+  control(container(text_with_logo(icon::layout(), "当前工站")), settings)
+    .style(theme::Container::Frame)
+    .into()
+}
+
+/// 工站配置
+pub fn profile<'a>(station: &'a Station, steps_input: &'a str) -> Element<'a, super::Message> {
+  let field = |label: &'a str, placeholder: &'a str, value: &'a str, on_input: fn(String) -> super::Message| {
+    row![
+      text(label).width(100),
+      text_input(placeholder, value).on_input(on_input)
+    ]
+    .spacing(8)
+    .align_items(iced::Alignment::Center)
+  };
+  let steps = steps_text(station, steps_input);
+  let settings = column![
+    row![
+      text("步骤：").width(100),
+      text_input("步骤标签, 逗号分隔, 按顺序执行; 为空执行全部", &steps).on_input(super::Message::SetStationSteps)
+    ]
+    .spacing(8)
+    .align_items(iced::Alignment::Center),
+    field("SN规则：", "为空使用产品规则", &station.sn_pattern, super::Message::SetStationSnPattern),
+    field("上传地址：", "http://", &station.upload_url, super::Message::SetStationUploadUrl),
+    field("前置脚本：", "测试开始前执行", &station.pre_hook, super::Message::SetStationPreHook),
+    field("后置脚本：", "测试结束后执行, 可引用 #result#", &station.post_hook, super::Message::SetStationPostHook),
+  ]
+  .spacing(8);
+  control(
+    container(text_with_logo(icon::edit_square(), format!("工站配置: {}", station.name))),
+    settings,
+  )
+  .style(theme::Container::Frame)
+  .into()
+}
@@ -0,0 +1,176 @@
+use iced::{
+  alignment, mouse,
+  widget::canvas::{self, Frame, Geometry, Path, Stroke, Text},
+  Color, Point, Rectangle, Renderer, Size,
+};
+
+use super::Message;
+use crate::{
+  plugins::spc::{Rule, Spc},
+  ui::components::Theme,
+};
+
+/// 坐标区边距: 左、上、右、下
+const MARGIN: [f32; 4] = [64.0, 16.0, 96.0, 24.0];
+/// 两图间距
+const GAP: f32 = 28.0;
+/// X-bar 图所占高度比例
+const XBAR_RATIO: f32 = 0.6;
+
+/// X-bar/R 控制图: 上为子组均值, 下为子组极差; 触发判异的子组标为错误色
+#[derive(Debug, Default)]
+pub struct Control {
+  pub spc: Option<Spc>,
+}
+
+/// 一幅图的中心线、控制限及各点
+struct Series<'a> {
+  title: &'a str,
+  center: f64,
+  ucl: f64,
+  lcl: f64,
+  values: Vec<f64>,
+  /// 各点是否判异
+  flagged: Vec<bool>,
+}
+
+impl Control {
+  fn label(frame: &mut Frame, content: String, position: Point, color: Color, align: alignment::Horizontal) {
+    frame.fill_text(Text {
+      content,
+      position,
+      color,
+      size: 12.0.into(),
+      horizontal_alignment: align,
+      vertical_alignment: alignment::Vertical::Center,
+      ..Text::default()
+    });
+  }
+
+  fn series(frame: &mut Frame, theme: &Theme, plot: Rectangle, series: Series) {
+    let ref palette = theme.0;
+    let bottom = plot.y + plot.height;
+    let right = plot.x + plot.width;
+    let axis = Stroke::default().with_color(palette.border).with_width(1.0);
+    frame.stroke(&Path::rectangle(plot.position(), plot.size()), axis);
+    Self::label(
+      frame,
+      series.title.to_string(),
+      Point::new(plot.x, plot.y - 8.0),
+      palette.text,
+      alignment::Horizontal::Left,
+    );
+    // 纵轴留出控制限外的余量
+    let (min, max) = series
+      .values
+      .iter()
+      .fold((series.lcl, series.ucl), |(min, max), x| (min.min(*x), max.max(*x)));
+    let pad = match max - min {
+      span if span > 0.0 => span * 0.1,
+      _ => 1.0,
+    };
+    let (min, max) = (min - pad, max + pad);
+    let y = |v: f64| bottom - ((v - min) / (max - min)) as f32 * plot.height;
+    for (name, value, color) in [
+      ("UCL", series.ucl, palette.warning),
+      ("CL", series.center, palette.success),
+      ("LCL", series.lcl, palette.warning),
+    ] {
+      frame.stroke(
+        &Path::line(Point::new(plot.x, y(value)), Point::new(right, y(value))),
+        Stroke::default().with_color(color).with_width(1.0),
+      );
+      let position = Point::new(right + 6.0, y(value));
+      Self::label(frame, format!("{name} {value:.4}"), position, color, alignment::Horizontal::Left);
+    }
+    for value in [min, max] {
+      let position = Point::new(plot.x - 6.0, y(value));
+      Self::label(frame, format!("{value:.3}"), position, palette.text, alignment::Horizontal::Right);
+    }
+    let step = match series.values.len() {
+      0 | 1 => plot.width,
+      n => plot.width / (n - 1) as f32,
+    };
+    let points: Vec<Point> = series
+      .values
+      .iter()
+      .enumerate()
+      .map(|(i, v)| Point::new(plot.x + step * i as f32, y(*v)))
+      .collect();
+    let path = Path::new(|builder| {
+      for (i, point) in points.iter().enumerate() {
+        match i {
+          0 => builder.move_to(*point),
+          _ => builder.line_to(*point),
+        }
+      }
+    });
+    frame.stroke(&path, Stroke::default().with_color(palette.waveform).with_width(1.5));
+    for (point, flagged) in points.into_iter().zip(series.flagged) {
+      match flagged {
+        true => frame.fill(&Path::circle(point, 5.0), palette.error),
+        false => frame.fill(&Path::circle(point, 3.0), palette.waveform),
+      }
+    }
+  }
+}
+
+impl canvas::Program<Message, Theme> for Control {
+  type State = ();
+
+  fn draw(
+    &self,
+    _state: &Self::State,
+    renderer: &Renderer,
+    theme: &Theme,
+    bounds: Rectangle,
+    _cursor: mouse::Cursor,
+  ) -> Vec<Geometry> {
+    let mut frame = Frame::new(renderer, bounds.size());
+    let [left, top, right, bottom] = MARGIN;
+    let width = (bounds.width - left - right).max(1.0);
+    let height = (bounds.height - top - bottom - GAP).max(2.0);
+    let xbar = Rectangle::new(Point::new(left, top), Size::new(width, height * XBAR_RATIO));
+    let range = Rectangle::new(
+      Point::new(left, top + xbar.height + GAP),
+      Size::new(width, height * (1.0 - XBAR_RATIO)),
+    );
+    let Some(spc) = &self.spc else {
+      let center = Point::new(bounds.width / 2.0, bounds.height / 2.0);
+      Self::label(&mut frame, "数据不足".to_string(), center, theme.0.text, alignment::Horizontal::Center);
+      return vec![frame.into_geometry()];
+    };
+    let flagged = |range: bool| -> Vec<bool> {
+      (0..spc.subgroups.len())
+        .map(|i| spc.signals.iter().any(|x| x.index == i && (x.rule == Rule::Range) == range))
+        .collect()
+    };
+    Self::series(
+      &mut frame,
+      theme,
+      xbar,
+      Series {
+        title: "X-bar",
+        center: spc.center,
+        ucl: spc.ucl,
+        lcl: spc.lcl,
+        values: spc.subgroups.iter().map(|x| x.mean).collect(),
+        flagged: flagged(false),
+      },
+    );
+    Self::series(
+      &mut frame,
+      theme,
+      range,
+      Series {
+        title: "R",
+        center: spc.r_bar,
+        ucl: spc.r_ucl,
+        lcl: spc.r_lcl,
+        values: spc.subgroups.iter().map(|x| x.range).collect(),
+        flagged: flagged(true),
+      },
+    );
+    vec![frame.into_geometry()]
+  }
+}
@@ -0,0 +1,227 @@
+//! SPC 控制图窗口
+use iced::{
+  widget::{button, canvas, column, container, horizontal_space, pick_list, row, scrollable, text},
+  window, Alignment, Command, Length, Size,
+};
+
+mod chart;
+use super::main_app::AppWindow;
+use crate::{
+  config::{
+    logger::{log, Tag},
+    theme, Config,
+  },
+  data::icon,
+  plugins::spc::{self, format_index, Spc, SUBGROUP, WINDOW},
+  ui::components::{helpers::text_with_logo, Column, Element},
+};
+
+/// 不过滤
+const ALL: &str = "全部";
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  SetStation(String),
+  Refresh,
+  Loaded(Result<Option<Spc>, String>),
+}
+
+/// 步骤的 SPC 统计
+pub struct App {
+  pub id: window::Id,
+  pub flag: Config,
+  pub tag: String,
+  pub station: String,
+  chart: chart::Control,
+  pub loading: bool,
+  pub error: Option<String>,
+}
+
+impl Default for App {
+  fn default() -> Self {
+    Self {
+      id: window::Id::MAIN,
+      flag: Config::default(),
+      tag: String::new(),
+      station: ALL.to_string(),
+      chart: chart::Control::default(),
+      loading: false,
+      error: None,
+    }
+  }
+}
+
+impl App {
+  /// 显示步骤的控制图, 如测试表或测试记录中选中的步骤; 窗口未打开时打开
+  pub fn open(&mut self, tag: String, station: String) -> Command<Message> {
+    self.tag = tag;
+    self.station = match station.is_empty() {
+      true => ALL.to_string(),
+      false => station,
+    };
+    self.chart.spc = None;
+    self.create()
+  }
+
+  fn refresh(&mut self) -> Command<Message> {
+    if self.tag.is_empty() {
+      return Command::none();
+    }
+    self.loading = true;
+    self.error = None;
+    let station = match self.station == ALL {
+      true => String::new(),
+      false => self.station.clone(),
+    };
+    Command::perform(spc::load(self.tag.clone(), station), |res| {
+      Message::Loaded(res.map_err(|e| e.to_string()))
+    })
+  }
+
+  fn filter_view(&self) -> Element<'_, Message> {
+    let stations: Vec<String> = std::iter::once(ALL.to_string())
+      .chain(self.flag.stations.iter().map(|x| x.name.clone()))
+      .collect();
+    row![
+      text(format!("步骤：{}", self.tag)),
+      "工站：",
+      pick_list(stations, Some(self.station.clone()), Message::SetStation),
+      text(format!("最近{WINDOW}次, 每{SUBGROUP}次一组")),
+      horizontal_space(),
+      button(text_with_logo(icon::sync(), "刷新")).on_press_maybe((!self.loading).then_some(Message::Refresh)),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center)
+    .into()
+  }
+
+  /// 过程能力及判异列表
+  fn summary_view<'a>(&self, spc: &'a Spc) -> Element<'a, Message> {
+    let limit = |v: Option<f64>| v.map(|x| x.to_string()).unwrap_or_else(|| "-".to_string());
+    let items = [
+      ("样本数", spc.count.to_string()),
+      ("均值", format!("{:.4}", spc.mean)),
+      ("σ", format!("{:.4}", spc.sigma)),
+      ("σ(组内)", format!("{:.4}", spc.sigma_within)),
+      ("LSL", limit(spc.low)),
+      ("USL", limit(spc.high)),
+      ("Cp", format_index(spc.cp)),
+      ("Cpk", format_index(spc.cpk)),
+    ];
+    let items = Column::with_children(items.into_iter().map(|(name, value)| {
+      row![container(text(name)).width(70), text(value)].spacing(8).into()
+    }))
+    .spacing(4);
+    let signals = Column::with_children(spc.signals.iter().rev().map(|x| {
+      text(x.to_string()).size(13).style(theme::Text::Error).into()
+    }))
+    .spacing(2);
+    column![
+      container(items).padding(10).style(theme::Container::Frame),
+      text(format!("判异 {}项", spc.signals.len())),
+      scrollable(signals).height(Length::Fill).style(theme::Scrollable::Normal),
+    ]
+    .spacing(8)
+    .width(220)
+    .into()
+  }
+}
+
+impl AppWindow for App {
+  type Event = Message;
+  type Flag = Config;
+  type Setting = Config;
+
+  fn new(flag: Self::Flag) -> Self {
+    let mut slf = Self::default();
+    slf.flag = flag;
+    slf
+  }
+
+  fn settings(flag: Self::Setting) -> window::Settings {
+    let ref cfg = flag.user_cfg;
+    let size = Size::new(cfg.width, cfg.height);
+    window::Settings {
+      icon: super::main_app::application_icon(),
+      size,
+      min_size: Some(size),
+      position: window::Position::Default,
+      resizable: cfg.resizable,
+      decorations: cfg.decorations,
+      transparent: cfg.transparent,
+      exit_on_close_request: true,
+      ..Default::default()
+    }
+  }
+
+  fn title(&self) -> String {
+    format!("SPC控制图 {}", self.tag)
+  }
+
+  fn create(&mut self) -> Command<Self::Event> {
+    if self.exist() {
+      return Command::batch([self.show(), self.focus(), self.refresh()]);
+    }
+    let (id, spawn_window) = window::spawn(Self::settings(self.flag.clone()));
+    self.id = id;
+    Command::batch([spawn_window, self.refresh()])
+  }
+
+  fn update(&mut self, event: Self::Event) -> Command<Self::Event> {
+    match event {
+      Message::SetStation(v) => {
+        self.station = v;
+        return self.refresh();
+      }
+      Message::Refresh => return self.refresh(),
+      Message::Loaded(res) => {
+        self.loading = false;
+        match res {
+          Ok(spc) => self.chart.spc = spc,
+          Err(e) => {
+            log::error(format!("[{}] SPC统计失败: {e}", self.tag), Tag::DatabaseOffline);
+            self.error = Some(format!("统计失败: {e}"));
+          }
+        }
+      }
+    }
+    Command::none()
+  }
+
+  fn view(&self) -> Element<'_, Self::Event> {
+    let chart = canvas(&self.chart).width(Length::Fill).height(Length::Fill);
+    let body: Element<'_, Message> = match &self.chart.spc {
+      Some(spc) => row![self.summary_view(spc), chart].spacing(12).into(),
+      None => chart.into(),
+    };
+    let mut content = column![self.filter_view(), container(body).height(Length::Fill)].spacing(8);
+    if let Some(e) = &self.error {
+      content = content.push(text(e).style(theme::Text::Error));
+    }
+    container(content)
+      .padding(20)
+      .width(Length::Fill)
+      .height(Length::Fill)
+      .into()
+  }
+
+  fn subscription(&self) -> iced::Subscription<Self::Event> {
+    iced::Subscription::batch([])
+  }
+
+  fn close(&mut self) -> Command<Self::Event> {
+    window::close(self.id)
+  }
+
+  fn show(&self) -> Command<Self::Event> {
+    window::change_mode(self.id, window::Mode::Windowed)
+  }
+
+  fn exist(&self) -> bool {
+    self.id != window::Id::MAIN
+  }
+
+  fn focus(&mut self) -> Command<Self::Event> {
+    window::gain_focus(self.id)
+  }
+}
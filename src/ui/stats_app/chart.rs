@@ -0,0 +1,194 @@
+use iced::{
+  alignment, mouse,
+  widget::canvas::{self, event, Frame, Geometry, Path, Stroke, Text},
+  Color, Point, Rectangle, Renderer, Size,
+};
+
+use super::Message;
+use crate::ui::components::Theme;
+
+/// 坐标区边距: 左、上、右、下
+const MARGIN: [f32; 4] = [48.0, 16.0, 48.0, 40.0];
+/// 累计比例参考线
+const REFERENCE: f32 = 0.8;
+
+/// 失败步骤帕累托图: 柱为失败次数(降序), 折线为累计比例; 点击柱选中步骤
+#[derive(Debug, Default)]
+pub struct Pareto {
+  /// `(tag, 失败次数)`, 降序
+  pub bars: Vec<(String, i64)>,
+}
+
+impl Pareto {
+  /// 坐标区
+  fn plot(bounds: Size) -> Rectangle {
+    let [left, top, right, bottom] = MARGIN;
+    Rectangle::new(
+      Point::new(left, top),
+      Size::new((bounds.width - left - right).max(1.0), (bounds.height - top - bottom).max(1.0)),
+    )
+  }
+
+  /// 光标下的柱
+  fn bar_at(&self, bounds: Rectangle, cursor: mouse::Cursor) -> Option<usize> {
+    let position = cursor.position_in(bounds)?;
+    let plot = Self::plot(bounds.size());
+    if self.bars.is_empty() || !plot.contains(position) {
+      return None;
+    }
+    let index = ((position.x - plot.x) / (plot.width / self.bars.len() as f32)) as usize;
+    (index < self.bars.len()).then_some(index)
+  }
+
+  fn label(frame: &mut Frame, content: String, position: Point, color: Color, align: alignment::Horizontal) {
+    frame.fill_text(Text {
+      content,
+      position,
+      color,
+      size: 12.0.into(),
+      horizontal_alignment: align,
+      vertical_alignment: alignment::Vertical::Center,
+      ..Text::default()
+    });
+  }
+}
+
+impl canvas::Program<Message, Theme> for Pareto {
+  type State = ();
+
+  fn update(
+    &self,
+    _state: &mut Self::State,
+    event: canvas::Event,
+    bounds: Rectangle,
+    cursor: mouse::Cursor,
+  ) -> (event::Status, Option<Message>) {
+    match event {
+      canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => match self.bar_at(bounds, cursor) {
+        Some(i) => (event::Status::Captured, Some(Message::Select(self.bars[i].0.clone()))),
+        None => (event::Status::Ignored, None),
+      },
+      _ => (event::Status::Ignored, None),
+    }
+  }
+
+  fn draw(
+    &self,
+    _state: &Self::State,
+    renderer: &Renderer,
+    theme: &Theme,
+    bounds: Rectangle,
+    cursor: mouse::Cursor,
+  ) -> Vec<Geometry> {
+    let ref palette = theme.0;
+    let mut frame = Frame::new(renderer, bounds.size());
+    let plot = Self::plot(bounds.size());
+    let bottom = plot.y + plot.height;
+    let axis = Stroke::default().with_color(palette.border).with_width(1.0);
+    frame.stroke(
+      &Path::line(Point::new(plot.x, plot.y), Point::new(plot.x, bottom)),
+      axis.clone(),
+    );
+    frame.stroke(
+      &Path::line(Point::new(plot.x, bottom), Point::new(plot.x + plot.width, bottom)),
+      axis.clone(),
+    );
+    frame.stroke(
+      &Path::line(Point::new(plot.x + plot.width, plot.y), Point::new(plot.x + plot.width, bottom)),
+      axis,
+    );
+    if self.bars.is_empty() {
+      Self::label(
+        &mut frame,
+        "无失败记录".to_string(),
+        plot.center(),
+        palette.text,
+        alignment::Horizontal::Center,
+      );
+      return vec![frame.into_geometry()];
+    }
+    let max = self.bars.iter().map(|x| x.1).max().unwrap_or(1).max(1) as f32;
+    let total = self.bars.iter().map(|x| x.1).sum::<i64>().max(1) as f32;
+    let slot = plot.width / self.bars.len() as f32;
+    let hovered = self.bar_at(bounds, cursor);
+    // 左轴: 次数, 右轴: 累计比例
+    for (value, y) in [(max, plot.y), (max / 2.0, plot.y + plot.height / 2.0), (0.0, bottom)] {
+      let position = Point::new(plot.x - 6.0, y);
+      Self::label(&mut frame, format!("{value:.0}"), position, palette.text, alignment::Horizontal::Right);
+    }
+    for p in [0.0, 0.5, 1.0] {
+      let position = Point::new(plot.x + plot.width + 6.0, bottom - plot.height * p);
+      let content = format!("{:.0}%", p * 100.0);
+      Self::label(&mut frame, content, position, palette.text, alignment::Horizontal::Left);
+    }
+    let reference = bottom - plot.height * REFERENCE;
+    frame.stroke(
+      &Path::line(Point::new(plot.x, reference), Point::new(plot.x + plot.width, reference)),
+      Stroke::default().with_color(palette.success).with_width(1.0),
+    );
+    let mut cumulative = 0.0;
+    let mut line = vec![];
+    for (i, (tag, count)) in self.bars.iter().enumerate() {
+      let height = *count as f32 / max * plot.height;
+      let x = plot.x + slot * i as f32;
+      let color = match hovered == Some(i) {
+        true => Color { a: 0.7, ..palette.error },
+        false => palette.error,
+      };
+      frame.fill_rectangle(
+        Point::new(x + slot * 0.15, bottom - height),
+        Size::new(slot * 0.7, height),
+        color,
+      );
+      let center = x + slot / 2.0;
+      Self::label(
+        &mut frame,
+        count.to_string(),
+        Point::new(center, bottom - height - 8.0),
+        palette.text,
+        alignment::Horizontal::Center,
+      );
+      // 标签按柱宽截断
+      let chars = ((slot / 7.0) as usize).max(1);
+      let mut tag: String = tag.chars().take(chars).collect();
+      if tag.chars().count() < self.bars[i].0.chars().count() {
+        tag.pop();
+        tag.push('…');
+      }
+      Self::label(
+        &mut frame,
+        tag,
+        Point::new(center, bottom + 14.0),
+        palette.text,
+        alignment::Horizontal::Center,
+      );
+      cumulative += *count as f32;
+      line.push(Point::new(center, bottom - plot.height * cumulative / total));
+    }
+    let path = Path::new(|builder| {
+      for (i, point) in line.iter().enumerate() {
+        match i {
+          0 => builder.move_to(*point),
+          _ => builder.line_to(*point),
+        }
+      }
+    });
+    frame.stroke(&path, Stroke::default().with_color(palette.waveform).with_width(2.0));
+    for point in line {
+      frame.fill(&Path::circle(point, 3.0), palette.waveform);
+    }
+    vec![frame.into_geometry()]
+  }
+
+  fn mouse_interaction(
+    &self,
+    _state: &Self::State,
+    bounds: Rectangle,
+    cursor: mouse::Cursor,
+  ) -> mouse::Interaction {
+    match self.bar_at(bounds, cursor) {
+      Some(_) => mouse::Interaction::Pointer,
+      None => mouse::Interaction::default(),
+    }
+  }
+}
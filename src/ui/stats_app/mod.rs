@@ -0,0 +1,299 @@
+//! 良率统计窗口
+use e_utils::time::{DateTime, Utc};
+use iced::{
+  widget::{button, canvas, column, container, horizontal_space, pick_list, row, scrollable, text, text_input},
+  window, Alignment, Command, Length, Size,
+};
+
+mod chart;
+use super::{
+  history_app::{self, parse_time},
+  main_app::AppWindow,
+};
+use crate::{
+  config::{
+    logger::{log, Tag},
+    theme, Config,
+  },
+  data::{icon, DB_SQLITE},
+  db::{
+    apis::run::{count_failed_steps, select_runs},
+    r#type::run::RunQuery,
+  },
+  plugins::stats::{yields, Yield, TOTAL},
+  ui::components::{helpers::text_with_logo, Column, Element},
+};
+
+/// 不过滤
+const ALL: &str = "全部";
+/// 帕累托图显示的步骤数量
+const TOP: i64 = 10;
+/// 默认统计最近的天数
+const DAYS: i64 = 7;
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  SetStation(String),
+  SetStart(String),
+  SetEnd(String),
+  Refresh,
+  Loaded(Result<(Vec<Yield>, Vec<(String, i64)>), String>),
+  /// 点击帕累托图的柱, 在测试记录中查看该步骤失败的记录
+  Select(String),
+}
+
+/// 良率统计
+pub struct App {
+  pub id: window::Id,
+  pub flag: Config,
+  pub station: String,
+  /// `YYYY-MM-DD`(UTC)或 RFC3339 时间
+  pub start: String,
+  pub end: String,
+  pub yields: Vec<Yield>,
+  pareto: chart::Pareto,
+  pub loading: bool,
+  pub error: Option<String>,
+}
+
+impl Default for App {
+  fn default() -> Self {
+    let start = Utc::now().timestamp() - (DAYS - 1) * 86400;
+    let start = DateTime::<Utc>::from_timestamp(start, 0).unwrap_or_default();
+    Self {
+      id: window::Id::MAIN,
+      flag: Config::default(),
+      station: ALL.to_string(),
+      start: start.format("%Y-%m-%d").to_string(),
+      end: String::new(),
+      yields: vec![],
+      pareto: chart::Pareto::default(),
+      loading: false,
+      error: None,
+    }
+  }
+}
+
+impl App {
+  /// 当前查询条件, 不分页
+  fn query(&self) -> Result<RunQuery, String> {
+    Ok(RunQuery {
+      station: (self.station != ALL).then(|| self.station.clone()),
+      start: parse_time(&self.start, false)?,
+      end: parse_time(&self.end, true)?,
+      limit: -1,
+      ..Default::default()
+    })
+  }
+
+  fn refresh(&mut self) -> Command<Message> {
+    let q = match self.query() {
+      Ok(q) => q,
+      Err(e) => {
+        self.error = Some(e);
+        return Command::none();
+      }
+    };
+    self.loading = true;
+    self.error = None;
+    Command::perform(
+      async move {
+        let pool = DB_SQLITE.read().await.pool();
+        let runs = select_runs(&pool, &q).await.map_err(|e| e.to_string())?;
+        let failed = count_failed_steps(&pool, &q, TOP).await.map_err(|e| e.to_string())?;
+        Ok((yields(&runs), failed))
+      },
+      Message::Loaded,
+    )
+  }
+
+  /// 测试记录中查看步骤最终失败的记录, 条件同当前统计
+  pub fn history_filter(&self, tag: String) -> history_app::Filter {
+    history_app::Filter {
+      station: self.station.clone(),
+      start: self.start.clone(),
+      end: self.end.clone(),
+      step: tag,
+      ..Default::default()
+    }
+  }
+
+  fn filter_view(&self) -> Element<'_, Message> {
+    let stations: Vec<String> = std::iter::once(ALL.to_string())
+      .chain(self.flag.stations.iter().map(|x| x.name.clone()))
+      .collect();
+    row![
+      "工站：",
+      pick_list(stations, Some(self.station.clone()), Message::SetStation),
+      "时间：",
+      text_input("2024-01-01", &self.start)
+        .width(200)
+        .on_input(Message::SetStart)
+        .on_submit(Message::Refresh),
+      "至",
+      text_input("2024-01-31", &self.end)
+        .width(200)
+        .on_input(Message::SetEnd)
+        .on_submit(Message::Refresh),
+      horizontal_space(),
+      button(text_with_logo(icon::sync(), "刷新")).on_press_maybe((!self.loading).then_some(Message::Refresh)),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center)
+    .into()
+  }
+
+  /// 按工站、日期的良率表
+  fn yields_view(&self) -> Element<'_, Message> {
+    let cell = |content: String, width: f32| container(text(content)).width(width);
+    let percent = |v: f64| format!("{:.1}%", v * 100.0);
+    let header = row![
+      cell("工站".to_string(), 100.0),
+      cell("日期".to_string(), 110.0),
+      cell("SN数".to_string(), 70.0),
+      cell("测试次数".to_string(), 80.0),
+      cell("直通率".to_string(), 80.0),
+      cell("最终良率".to_string(), 80.0),
+      cell("复测率".to_string(), 80.0),
+    ]
+    .spacing(8);
+    let rows = Column::with_children(self.yields.iter().map(|x| {
+      let content = row![
+        cell(x.station.clone(), 100.0),
+        cell(x.day.clone(), 110.0),
+        cell(x.units.to_string(), 70.0),
+        cell(x.runs.to_string(), 80.0),
+        cell(percent(x.first_pass_yield()), 80.0),
+        cell(percent(x.final_yield()), 80.0),
+        cell(percent(x.retest_rate()), 80.0),
+      ]
+      .spacing(8);
+      match x.day == TOTAL {
+        true => container(content).style(theme::Container::Frame).into(),
+        false => content.into(),
+      }
+    }))
+    .spacing(2);
+    column![
+      header,
+      scrollable(rows).height(Length::Fill).style(theme::Scrollable::Normal)
+    ]
+    .spacing(6)
+    .into()
+  }
+}
+
+impl AppWindow for App {
+  type Event = Message;
+  type Flag = Config;
+  type Setting = Config;
+
+  fn new(flag: Self::Flag) -> Self {
+    let mut slf = Self::default();
+    slf.flag = flag;
+    slf
+  }
+
+  fn settings(flag: Self::Setting) -> window::Settings {
+    let ref cfg = flag.user_cfg;
+    let size = Size::new(cfg.width, cfg.height);
+    window::Settings {
+      icon: super::main_app::application_icon(),
+      size,
+      min_size: Some(size),
+      position: window::Position::Default,
+      resizable: cfg.resizable,
+      decorations: cfg.decorations,
+      transparent: cfg.transparent,
+      exit_on_close_request: true,
+      ..Default::default()
+    }
+  }
+
+  fn title(&self) -> String {
+    format!("良率统计")
+  }
+
+  fn create(&mut self) -> Command<Self::Event> {
+    if self.exist() {
+      return Command::batch([self.show(), self.focus(), self.refresh()]);
+    }
+    let (id, spawn_window) = window::spawn(Self::settings(self.flag.clone()));
+    self.id = id;
+    Command::batch([spawn_window, self.refresh()])
+  }
+
+  fn update(&mut self, event: Self::Event) -> Command<Self::Event> {
+    match event {
+      Message::SetStation(v) => {
+        self.station = v;
+        return self.refresh();
+      }
+      Message::SetStart(v) => self.start = v,
+      Message::SetEnd(v) => self.end = v,
+      Message::Refresh => return self.refresh(),
+      Message::Loaded(res) => {
+        self.loading = false;
+        match res {
+          Ok((yields, failed)) => {
+            self.yields = yields;
+            self.pareto.bars = failed;
+          }
+          Err(e) => {
+            log::error(format!("统计良率失败: {e}"), Tag::DatabaseOffline);
+            self.error = Some(format!("统计失败: {e}"));
+          }
+        }
+      }
+      // 由主窗口打开测试记录
+      Message::Select(_) => {}
+    }
+    Command::none()
+  }
+
+  fn view(&self) -> Element<'_, Self::Event> {
+    let mut content = column![
+      self.filter_view(),
+      row![
+        container(self.yields_view()).width(Length::FillPortion(1)),
+        column![
+          text(format!("失败步骤 Top {TOP} (点击查看测试记录)")),
+          canvas(&self.pareto).width(Length::Fill).height(Length::Fill),
+        ]
+        .spacing(6)
+        .width(Length::FillPortion(1)),
+      ]
+      .spacing(12)
+      .height(Length::Fill),
+    ]
+    .spacing(8);
+    if let Some(e) = &self.error {
+      content = content.push(text(e).style(theme::Text::Error));
+    }
+    container(content)
+      .padding(20)
+      .width(Length::Fill)
+      .height(Length::Fill)
+      .into()
+  }
+
+  fn subscription(&self) -> iced::Subscription<Self::Event> {
+    iced::Subscription::batch([])
+  }
+
+  fn close(&mut self) -> Command<Self::Event> {
+    window::close(self.id)
+  }
+
+  fn show(&self) -> Command<Self::Event> {
+    window::change_mode(self.id, window::Mode::Windowed)
+  }
+
+  fn exist(&self) -> bool {
+    self.id != window::Id::MAIN
+  }
+
+  fn focus(&mut self) -> Command<Self::Event> {
+    window::gain_focus(self.id)
+  }
+}
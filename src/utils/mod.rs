@@ -1,7 +1,7 @@
 // //! Utilility functions used throughout the codebase.
 pub mod fs;
 // use std::path::Path;
-// pub mod time;
+pub mod time;
 // /// Returns filename of path
 // pub fn filename(path: &Path) -> &str {
 //   path.file_name().and_then(|f| f.to_str()).unwrap_or_default()
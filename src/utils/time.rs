@@ -28,6 +28,11 @@ impl Time {
         self.duration.as_secs_f32()
     }
 
+    /// 未停止时的实时耗时
+    pub fn running(&self) -> f32 {
+        self.start.elapsed().as_secs_f32()
+    }
+
     pub fn init() -> Self {
         Self::default()
     }